@@ -3,91 +3,2380 @@
 #[macro_use] extern crate bson;
 #[macro_use] extern crate rocket;
 
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
+use rocket::fairing::AdHoc;
 use rocket::response::NamedFile;
+use rocket::State;
 
-// routing for the index file
+use crate::config::Settings;
+
+// routing for the index file, only mounted when there is a frontend to serve
 #[get("/")]
-fn index() -> Option<NamedFile> {
-    NamedFile::open(Path::new("target/deploy/index.html")).ok()
+fn index(settings: State<Settings>) -> Option<NamedFile> {
+    NamedFile::open(settings.static_dir.as_ref()?.join("index.html")).ok()
 }
 
 // routing for static files
 #[get("/<file..>")]
-fn files(file: PathBuf) -> Option<NamedFile> {
-    NamedFile::open(Path::new("target/deploy/").join(file)).ok()
+fn files(file: PathBuf, settings: State<Settings>) -> Option<NamedFile> {
+    NamedFile::open(settings.static_dir.as_ref()?.join(file)).ok()
+}
+
+mod config {
+    use std::env;
+    use std::path::PathBuf;
+
+    use rocket::config::{Config, ConfigError};
+    use rocket::Rocket;
+
+    use crate::accounts;
+    use crate::store;
+
+    const DEFAULT_MONGODB_URI: &str = "mongodb://localhost:27017/";
+    const DEFAULT_DATABASE: &str = "Connect4DB";
+    const DEFAULT_COLLECTION: &str = "games";
+    const DEFAULT_USERS_COLLECTION: &str = "users";
+    const DEFAULT_GAMES_FILE: &str = "games.json";
+    const DEFAULT_USERS_FILE: &str = "users.json";
+
+    pub enum StoreSettings {
+        Mongo {
+            uri: String,
+            database: String,
+            collection: String,
+            users_collection: String,
+        },
+        File {
+            games: PathBuf,
+            users: PathBuf,
+        },
+    }
+
+    // backend settings read from Rocket.toml or ROCKET_* environment variables, relative
+    // paths are taken from the directory holding Rocket.toml; the bind address and port
+    // are Rocket's own address and port settings
+    pub struct Settings {
+        pub store: StoreSettings,
+        // None when the frontend hasn't been built, the server then only serves the API
+        pub static_dir: Option<PathBuf>,
+        // account keys allowed to delete games
+        pub admins: Vec<String>,
+    }
+
+    impl Settings {
+        pub fn from_config(config: &Config) -> Result<Settings, String> {
+            let store = match string(config, "game_store", "mongodb")?.as_str() {
+                "mongodb" => {
+                    let uri = string(config, "mongodb_uri", DEFAULT_MONGODB_URI)?;
+                    if !uri.starts_with("mongodb://") && !uri.starts_with("mongodb+srv://") {
+                        return Err(format!("mongodb_uri {:?} isn't a mongodb:// URI", uri));
+                    }
+                    StoreSettings::Mongo {
+                        uri,
+                        database: name(config, "mongodb_database", DEFAULT_DATABASE)?,
+                        collection: name(config, "mongodb_collection", DEFAULT_COLLECTION)?,
+                        users_collection: name(config, "mongodb_users_collection", DEFAULT_USERS_COLLECTION)?,
+                    }
+                }
+                "file" => StoreSettings::File {
+                    games: file(config, "games_file", DEFAULT_GAMES_FILE)?,
+                    users: file(config, "users_file", DEFAULT_USERS_FILE)?,
+                },
+                other => {
+                    return Err(format!("game_store is {:?}, expected \"mongodb\" or \"file\"", other));
+                }
+            };
+
+            let static_dir = match string(config, "static_dir", "")?.as_str() {
+                "" => default_static_dir(),
+                dir => config.root_relative(dir),
+            };
+            let static_dir = if static_dir.is_dir() {
+                Some(static_dir)
+            } else {
+                eprintln!(
+                    "Warning: static_dir {} isn't a directory, serving the API only; build the frontend with `cargo web deploy` or set static_dir",
+                    static_dir.display()
+                );
+                None
+            };
+
+            let admins = names(config, "admins")?;
+
+            Ok(Settings { store, static_dir, admins })
+        }
+    }
+
+    // the frontend's deploy directory beside the server's build directory, target/deploy
+    // for a server in target/debug or target/release, else target/deploy under the
+    // working directory
+    fn default_static_dir() -> PathBuf {
+        env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.parent()?.parent()?.join("deploy")))
+            .unwrap_or_else(|| PathBuf::from("target/deploy"))
+    }
+
+    // the string value of key, default when it isn't set
+    fn string(config: &Config, key: &str, default: &str) -> Result<String, String> {
+        match config.get_str(key) {
+            Ok(value) => Ok(value.to_string()),
+            Err(ConfigError::Missing(_)) => Ok(default.to_string()),
+            Err(_) => Err(format!("{} must be a string", key)),
+        }
+    }
+
+    // account keys from a list of names or a comma separated string, none when it isn't set
+    fn names(config: &Config, key: &str) -> Result<Vec<String>, String> {
+        let names = match config.get_slice(key) {
+            Ok(values) => values
+                .iter()
+                .map(|value| value.as_str().map(String::from))
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(|| format!("{} must be a list of names", key))?,
+            Err(ConfigError::Missing(_)) => Vec::new(),
+            Err(_) => string(config, key, "")?.split(',').map(String::from).collect(),
+        };
+        Ok(names
+            .iter()
+            .map(|name| accounts::key(name))
+            .filter(|key| !key.is_empty())
+            .collect())
+    }
+
+    // a file path, relative to Rocket.toml
+    fn file(config: &Config, key: &str, default: &str) -> Result<PathBuf, String> {
+        let path = config.root_relative(string(config, key, default)?);
+        if path.is_dir() {
+            return Err(format!("{} {} is a directory", key, path.display()));
+        }
+        Ok(path)
+    }
+
+    // a database or collection name
+    fn name(config: &Config, key: &str, default: &str) -> Result<String, String> {
+        let value = string(config, key, default)?;
+        if value.is_empty() || value.contains(|c: char| c == '$' || c == '/' || c == ' ') {
+            return Err(format!("{} {:?} isn't a valid MongoDB name", key, value));
+        }
+        Ok(value)
+    }
+
+    // reads the settings and opens the game store, refusing to launch if either fails
+    pub fn attach(rocket: Rocket) -> Result<Rocket, Rocket> {
+        let settings = match Settings::from_config(rocket.config()) {
+            Ok(settings) => settings,
+            Err(error) => {
+                eprintln!("Invalid configuration: {}", error);
+                return Err(rocket);
+            }
+        };
+
+        let stores = store::open(&settings.store)
+            .and_then(|games| Ok((games, store::open_users(&settings.store)?)));
+        // the frontend's routes would only answer 404 without its files
+        let rocket = match settings.static_dir {
+            Some(_) => rocket.mount("/", routes![crate::index, crate::files]),
+            None => rocket,
+        };
+        match stores {
+            Ok((games, users)) => Ok(rocket.manage(games).manage(users).manage(settings)),
+            Err(error) => {
+                eprintln!("Failed to open game store: {}", error);
+                Err(rocket)
+            }
+        }
+    }
 }
 
 mod games {
-    use bson::Bson;
-    use mongodb::Collection;
+    use std::cmp::Ordering;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use bson::{Bson, Document};
+    use engine::{GameKind, GameState, Inventory, Move, Outcome, Player, Variant};
+    use rocket::http::Status;
+    use rocket::request::Form;
+    use rocket::response::status;
     use rocket::State;
     use rocket_contrib::json::Json;
     use serde::{Serialize, Deserialize};
 
+    use crate::accounts::{self, LoggedIn};
+    use crate::config::Settings;
+    use crate::store::{GameStore, UserStore};
+
+    // body of a rejected request, code says what kind of failure it was
+    #[derive(Serialize, Debug)]
+    pub struct ErrorBody {
+        error: String,
+        code: &'static str,
+    }
+
+    impl ErrorBody {
+        pub fn new(status: Status, error: String) -> status::Custom<Json<ErrorBody>> {
+            status::Custom(status, Json(ErrorBody::body(status, error)))
+        }
+
+        fn body(status: Status, error: String) -> ErrorBody {
+            let code = match status.code {
+                400 => "malformed_request",
+                401 => "not_logged_in",
+                403 => "forbidden",
+                404 => "not_found",
+                409 => "conflict",
+                422 => "invalid_input",
+                500 => "storage_failure",
+                _ => "error",
+            };
+            ErrorBody { error, code }
+        }
+    }
+
+    // the id of a newly stored game
+    #[derive(Serialize, Debug)]
+    pub struct Created {
+        id: String,
+    }
+
     // Rust representation of game objects
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Debug, Clone)]
     #[allow(non_snake_case)]
     pub struct Game {
-        gameNumber: String,
-        gameType: String,
-        Player1Name: String,
-        Player2Name: String,
-        WinnerName: String,
-        GameDate: i64,
+        pub(crate) gameNumber: String,
+        pub(crate) gameType: String,
+        pub(crate) Player1Name: String,
+        pub(crate) Player2Name: String,
+        pub(crate) WinnerName: String,
+        pub(crate) GameDate: i64,
+        // letters each player had left, TOOT-OTTO only
+        #[serde(default)]
+        pub(crate) Player1Inventory: Option<Inventory>,
+        #[serde(default)]
+        pub(crate) Player2Inventory: Option<Inventory>,
+        // every move in the order played
+        #[serde(default)]
+        pub(crate) Moves: Vec<Move>,
+        // the players took moves back during the game
+        #[serde(default)]
+        pub(crate) UsedUndo: bool,
+        // the computer's difficulty, only for games against the Computer
+        #[serde(default)]
+        pub(crate) Difficulty: Option<String>,
+        // the posting browser's own id for the game, so a resent game is only stored once
+        #[serde(default)]
+        pub(crate) ClientId: Option<String>,
+        // board size and line length of Connect-N games, None for the standard board
+        #[serde(default)]
+        pub(crate) Variant: Option<Variant>,
+    }
+
+    impl Game {
+        // record of a game the server refereed from start to finish
+        pub fn finished(state: &GameState, player1: &str, player2: &str) -> Game {
+            let date = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as i64)
+                .unwrap_or(0);
+            Game {
+                gameNumber: String::new(),
+                gameType: state.kind().to_string(),
+                Player1Name: player1.to_string(),
+                Player2Name: player2.to_string(),
+                WinnerName: match state.outcome() {
+                    Some(Outcome::Win(Player::One)) => player1.to_string(),
+                    Some(Outcome::Win(Player::Two)) => player2.to_string(),
+                    _ => String::from("Draw"),
+                },
+                GameDate: date,
+                Player1Inventory: state.inventory(Player::One),
+                Player2Inventory: state.inventory(Player::Two),
+                Moves: state.history().to_vec(),
+                UsedUndo: false,
+                Difficulty: None,
+                ClientId: None,
+                Variant: Some(state.variant()).filter(|&variant| variant != state.kind().variant()),
+            }
+        }
+
+        // replays the moves with the rules engine and checks they end the way the
+        // record says, the inventories are taken from the replay
+        fn verify(&mut self) -> Result<(), String> {
+            let kind = self.gameType.parse::<GameKind>()?;
+            if self.Moves.is_empty() {
+                return Err(String::from("the game's moves are missing"));
+            }
+            // the computer is only ever player 2 and "Draw" only ever marks a draw
+            if accounts::is_reserved(&self.Player1Name) {
+                return Err(format!("{} can't be player 1", self.Player1Name));
+            }
+            if self.Player2Name != "Computer" && accounts::is_reserved(&self.Player2Name) {
+                return Err(format!("{} can't be player 2", self.Player2Name));
+            }
+            if self.Player2Name == "Computer" && self.Difficulty.is_none() {
+                return Err(String::from("games against the Computer need a difficulty"));
+            }
+            if let Some(difficulty) = &self.Difficulty {
+                if self.Player2Name != "Computer" {
+                    return Err(String::from("only games against the Computer have a difficulty"));
+                }
+                if !DIFFICULTIES.contains(&difficulty.as_str()) {
+                    return Err(format!("unknown difficulty {:?}", difficulty));
+                }
+            }
+
+            let variant = match self.Variant {
+                Some(variant) if kind.has_fixed_board() && variant != kind.variant() => {
+                    return Err(format!("{} is only played on {}, not {}", kind, kind.variant(), variant))
+                }
+                Some(variant) => {
+                    variant.check()?;
+                    variant
+                }
+                None => kind.variant(),
+            };
+            // a game on its kind's own board is stored without a variant
+            if variant == kind.variant() {
+                self.Variant = None;
+            }
+
+            let mut state = GameState::with_variant(kind, variant);
+            for (number, &mv) in self.Moves.iter().enumerate() {
+                if let Err(error) = state.apply_move(mv) {
+                    return Err(format!("move {} is not legal in {}: {}", number + 1, kind, error));
+                }
+            }
+
+            let winner = match state.outcome() {
+                Some(Outcome::Win(Player::One)) => self.Player1Name.as_str(),
+                Some(Outcome::Win(Player::Two)) => self.Player2Name.as_str(),
+                Some(Outcome::Draw) => "Draw",
+                None => return Err(String::from("the moves don't finish the game")),
+            };
+            if self.WinnerName != winner {
+                return Err(format!(
+                    "the moves end with {} but the winner was given as {}",
+                    if winner == "Draw" { String::from("a draw") } else { format!("{} winning", winner) },
+                    self.WinnerName
+                ));
+            }
+
+            self.Player1Inventory = state.inventory(Player::One);
+            self.Player2Inventory = state.inventory(Player::Two);
+            Ok(())
+        }
+
+        // neither seat can be a registered player other than whoever is logged in, so
+        // nobody can put results on someone else's record, two registered players meet in
+        // a room instead; registered players are recorded under their account's spelling
+        fn claim_seats(&mut self, user: &Option<LoggedIn>, users: &dyn UserStore) -> Result<(), String> {
+            let player1 = accounts::claim(&self.Player1Name, user, users)?;
+            let player2 = accounts::claim(&self.Player2Name, user, users)?;
+            if self.WinnerName == self.Player1Name {
+                self.WinnerName = player1.clone();
+            } else if self.WinnerName == self.Player2Name {
+                self.WinnerName = player2.clone();
+            }
+            self.Player1Name = player1;
+            self.Player2Name = player2;
+            Ok(())
+        }
+    }
+
+    // the computer's levels, easiest first
+    pub const DIFFICULTIES: [&str; 4] = ["Easy", "Medium", "Hard", "Perfect"];
+
+    const DEFAULT_LIMIT: usize = 20;
+    const MAX_LIMIT: usize = 100;
+
+    // query string of GET /games, every parameter is optional
+    #[derive(FromForm, Debug)]
+    #[allow(non_snake_case)]
+    pub struct GameQuery {
+        gameType: Option<String>,
+        // either player
+        player: Option<String>,
+        winner: Option<String>,
+        // GameDate range in milliseconds, both ends included
+        from: Option<i64>,
+        to: Option<i64>,
+        sort: Option<String>,
+        order: Option<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    pub enum SortField {
+        Date,
+        GameType,
+        Player1,
+        Player2,
+        Winner,
+    }
+
+    impl SortField {
+        // the Game field sorted on, also its name in stored documents
+        pub fn key(self) -> &'static str {
+            match self {
+                SortField::Date => "GameDate",
+                SortField::GameType => "gameType",
+                SortField::Player1 => "Player1Name",
+                SortField::Player2 => "Player2Name",
+                SortField::Winner => "WinnerName",
+            }
+        }
+
+        fn compare(self, a: &Game, b: &Game) -> Ordering {
+            match self {
+                SortField::Date => a.GameDate.cmp(&b.GameDate),
+                SortField::GameType => a.gameType.cmp(&b.gameType),
+                SortField::Player1 => a.Player1Name.cmp(&b.Player1Name),
+                SortField::Player2 => a.Player2Name.cmp(&b.Player2Name),
+                SortField::Winner => a.WinnerName.cmp(&b.WinnerName),
+            }
+        }
     }
 
-    #[get("/games")]
-    pub fn get(collection: State<Collection>) -> Json<Vec<Game>> {
-        let mut games = Vec::new();
-        
-        // get all the documents in the collection
-        if let Ok(cursor) = collection.find(doc!{}, None) {
-            for document in cursor.filter_map(Result::ok) {
-                // if document can be converted from BSON, add to vec
-                if let Ok(game) = bson::from_bson(Bson::Document(document)) {
-                    games.push(game);
+    // a checked GameQuery, the stores only see these
+    #[derive(Debug)]
+    pub struct Query {
+        game_type: Option<String>,
+        player: Option<String>,
+        winner: Option<String>,
+        from: Option<i64>,
+        to: Option<i64>,
+        pub sort: SortField,
+        pub descending: bool,
+        pub offset: usize,
+        pub limit: usize,
+    }
+
+    impl GameQuery {
+        fn check(self) -> Result<Query, String> {
+            if let Some(game_type) = &self.gameType {
+                game_type.parse::<GameKind>()?;
+            }
+            let sort = match self.sort.as_ref().map(String::as_str) {
+                None | Some("date") => SortField::Date,
+                Some("gameType") => SortField::GameType,
+                Some("player1") => SortField::Player1,
+                Some("player2") => SortField::Player2,
+                Some("winner") => SortField::Winner,
+                Some(other) => {
+                    return Err(format!(
+                        "can't sort by {:?}, expected date, gameType, player1, player2 or winner",
+                        other
+                    ))
                 }
+            };
+            let descending = match self.order.as_ref().map(String::as_str) {
+                None | Some("desc") => true,
+                Some("asc") => false,
+                Some(other) => return Err(format!("order is {:?}, expected asc or desc", other)),
+            };
+            let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+            if limit == 0 || limit > MAX_LIMIT {
+                return Err(format!("limit must be between 1 and {}", MAX_LIMIT));
+            }
+
+            Ok(Query {
+                game_type: self.gameType,
+                player: self.player,
+                winner: self.winner,
+                from: self.from,
+                to: self.to,
+                sort,
+                descending,
+                offset: self.offset.unwrap_or(0),
+                limit,
+            })
+        }
+    }
+
+    impl Query {
+        pub fn matches(&self, game: &Game) -> bool {
+            let equals = |wanted: &Option<String>, value: &str| match wanted {
+                Some(wanted) => wanted == value,
+                None => true,
+            };
+            let player = match &self.player {
+                Some(player) => game.Player1Name == *player || game.Player2Name == *player,
+                None => true,
+            };
+            equals(&self.game_type, &game.gameType)
+                && equals(&self.winner, &game.WinnerName)
+                && player
+                && self.from.map_or(true, |from| game.GameDate >= from)
+                && self.to.map_or(true, |to| game.GameDate <= to)
+        }
+
+        // sorts, filters and pages games in memory, for stores that can't do it themselves
+        pub fn apply(&self, mut games: Vec<Game>) -> Page {
+            games.retain(|game| self.matches(game));
+            // stable, so ties are in the order they were saved in, and reversed with everything
+            // else when descending, like the _id tie-break does for MongoDB
+            games.sort_by(|a, b| self.sort.compare(a, b));
+            if self.descending {
+                games.reverse();
+            }
+            let total = games.len();
+            let items = games.into_iter().skip(self.offset).take(self.limit).collect();
+            Page { total, offset: self.offset, limit: self.limit, items }
+        }
+
+        // the same filter as a MongoDB query document
+        pub fn filter(&self) -> Document {
+            let mut filter = Document::new();
+            if let Some(game_type) = &self.game_type {
+                filter.insert("gameType", game_type.clone());
+            }
+            if let Some(winner) = &self.winner {
+                filter.insert("WinnerName", winner.clone());
+            }
+            if let Some(player) = &self.player {
+                filter.insert("$or", vec![
+                    Bson::Document(doc! { "Player1Name": player.clone() }),
+                    Bson::Document(doc! { "Player2Name": player.clone() }),
+                ]);
+            }
+            let mut date = Document::new();
+            if let Some(from) = self.from {
+                date.insert("$gte", from);
             }
+            if let Some(to) = self.to {
+                date.insert("$lte", to);
+            }
+            if !date.is_empty() {
+                filter.insert("GameDate", date);
+            }
+            filter
         }
-        
-        Json(games)
+    }
+
+    // one page of games matching a query, and how many match altogether
+    #[derive(Serialize, Debug)]
+    pub struct Page {
+        pub total: usize,
+        pub offset: usize,
+        pub limit: usize,
+        pub items: Vec<Game>,
+    }
+
+    #[get("/games?<query..>")]
+    pub fn get(query: Form<GameQuery>, store: State<Box<dyn GameStore>>) -> Result<Json<Page>, status::Custom<Json<ErrorBody>>> {
+        let query = query
+            .into_inner()
+            .check()
+            .map_err(|error| ErrorBody::new(Status::UnprocessableEntity, error))?;
+        store
+            .find(&query)
+            .map(Json)
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))
     }
 
     #[post("/games", format="json", data="<game>")]
-    pub fn post(game: Json<Game>, collection: State<Collection>) {
-        // if game can be converted to document, store in db
-        if let Ok(Bson::Document(document)) = bson::to_bson(&game.into_inner()) {
-            collection.insert_one(document, None).unwrap();
+    pub fn post(
+        game: Json<Game>,
+        user: Option<LoggedIn>,
+        store: State<Box<dyn GameStore>>,
+        users: State<Box<dyn UserStore>>,
+    ) -> Result<status::Created<Json<Created>>, status::Custom<Json<ErrorBody>>> {
+        let mut game = game.into_inner();
+
+        // a browser resending a game it couldn't confirm gets the id it was stored under,
+        // without verifying it again; the store catches resends racing each other
+        if let Some(client_id) = &game.ClientId {
+            let stored = store
+                .by_client_id(client_id)
+                .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+            if let Some(stored) = stored {
+                let id = stored.gameNumber;
+                return Ok(status::Created(format!("/games/{}", id), Some(Json(Created { id }))));
+            }
+        }
+
+        // only store games whose moves really produce the posted result
+        if let Err(error) = game.verify() {
+            return Err(ErrorBody::new(Status::UnprocessableEntity, error));
+        }
+        game.claim_seats(&user, &**users)
+            .map_err(|error| ErrorBody::new(Status::Forbidden, error))?;
+
+        let id = store
+            .insert(&game)
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+        Ok(status::Created(format!("/games/{}", id), Some(Json(Created { id }))))
+    }
+
+    #[get("/games/<id>")]
+    pub fn get_one(id: String, store: State<Box<dyn GameStore>>) -> Result<Json<Game>, status::Custom<Json<ErrorBody>>> {
+        match store.get(&id) {
+            Ok(Some(game)) => Ok(Json(game)),
+            Ok(None) => Err(ErrorBody::new(Status::NotFound, format!("no game with id {}", id))),
+            Err(error) => Err(ErrorBody::new(Status::InternalServerError, error)),
+        }
+    }
+
+    // only for the accounts listed in the admins setting
+    #[delete("/games/<id>")]
+    pub fn delete(
+        id: String,
+        user: Option<LoggedIn>,
+        settings: State<Settings>,
+        store: State<Box<dyn GameStore>>,
+    ) -> Result<status::NoContent, status::Custom<Json<ErrorBody>>> {
+        let name = match user {
+            Some(LoggedIn(name)) => name,
+            None => return Err(ErrorBody::new(Status::Unauthorized, String::from("log in to delete games"))),
+        };
+        if !settings.admins.contains(&accounts::key(&name)) {
+            return Err(ErrorBody::new(Status::Forbidden, format!("{} can't delete games", name)));
+        }
+        match store.delete(&id) {
+            Ok(true) => Ok(status::NoContent),
+            Ok(false) => Err(ErrorBody::new(Status::NotFound, format!("no game with id {}", id))),
+            Err(error) => Err(ErrorBody::new(Status::InternalServerError, error)),
+        }
+    }
+
+    // errors from Rocket itself, such as bodies that aren't valid JSON, get the same body
+    // as the routes' own errors
+    #[catch(400)]
+    pub fn bad_request() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::BadRequest, String::from("the request is malformed")))
+    }
+
+    #[catch(401)]
+    pub fn unauthorized() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::Unauthorized, String::from("log in first")))
+    }
+
+    #[catch(403)]
+    pub fn forbidden() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::Forbidden, String::from("not allowed")))
+    }
+
+    #[catch(404)]
+    pub fn not_found() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::NotFound, String::from("not found")))
+    }
+
+    #[catch(409)]
+    pub fn conflict() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::Conflict, String::from("conflicts with the current state")))
+    }
+
+    #[catch(422)]
+    pub fn unprocessable() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::UnprocessableEntity, String::from("the request body isn't what was expected")))
+    }
+
+    #[catch(500)]
+    pub fn internal_error() -> Json<ErrorBody> {
+        Json(ErrorBody::body(Status::InternalServerError, String::from("the server failed")))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::accounts::tests::Users;
+
+        // Player 1 wins down column 0
+        fn connect4(player1: &str, player2: &str) -> Game {
+            let mut state = GameState::new(GameKind::Connect4);
+            for &column in [0, 1, 0, 1, 0, 1, 0].iter() {
+                state.apply_move(Move::drop(column)).unwrap();
+            }
+            Game::finished(&state, player1, player2)
+        }
+
+        #[test]
+        fn verify_accepts_the_replayed_result() {
+            let mut game = connect4("alice", "bob");
+            game.Variant = Some(Variant::STANDARD);
+            assert_eq!(game.verify(), Ok(()));
+            assert_eq!(game.Variant, None);
+        }
+
+        #[test]
+        fn verify_rejects_a_different_result() {
+            let mut game = connect4("alice", "bob");
+            game.WinnerName = String::from("bob");
+            assert!(game.verify().is_err());
+            game.WinnerName = String::from("Draw");
+            assert!(game.verify().is_err());
+
+            let mut game = connect4("alice", "bob");
+            game.Moves.pop();
+            assert!(game.verify().is_err());
+
+            let mut game = connect4("alice", "bob");
+            game.gameType = String::from("Checkers");
+            assert!(game.verify().is_err());
+        }
+
+        #[test]
+        fn verify_keeps_reserved_names_for_the_server() {
+            assert!(connect4("Computer", "bob").verify().is_err());
+            assert!(connect4("alice", "Draw").verify().is_err());
+            assert!(connect4("alice", " draw").verify().is_err());
+            assert!(connect4("alice", "computer").verify().is_err());
+
+            // the computer always plays at some difficulty
+            let mut game = connect4("alice", "Computer");
+            assert!(game.verify().is_err());
+            game.Difficulty = Some(String::from("Hard"));
+            assert_eq!(game.verify(), Ok(()));
+
+            let mut game = connect4("alice", "bob");
+            game.Difficulty = Some(String::from("Hard"));
+            assert!(game.verify().is_err());
+        }
+
+        #[test]
+        fn seats_are_claimed_for_both_players() {
+            let users = Users::new(&["Bob", "carol"]);
+            let bob = Some(LoggedIn(String::from("Bob")));
+
+            // a loss can't be put on another player's record from either seat
+            assert!(connect4("alice", "carol").claim_seats(&bob, &users).is_err());
+            assert!(connect4("carol", "alice").claim_seats(&None, &users).is_err());
+
+            let mut game = connect4(" bob", "alice");
+            assert_eq!(game.claim_seats(&bob, &users), Ok(()));
+            assert_eq!((game.Player1Name.as_str(), game.Player2Name.as_str()), ("Bob", "alice"));
+            assert_eq!(game.WinnerName, "Bob");
+
+            let mut game = connect4("alice", "Computer");
+            assert_eq!(game.claim_seats(&None, &users), Ok(()));
+            assert_eq!(game.WinnerName, "alice");
+        }
+
+        fn query(sort: Option<&str>, order: Option<&str>, limit: Option<usize>) -> GameQuery {
+            GameQuery {
+                gameType: None,
+                player: None,
+                winner: None,
+                from: None,
+                to: None,
+                sort: sort.map(String::from),
+                order: order.map(String::from),
+                limit,
+                offset: None,
+            }
+        }
+
+        #[test]
+        fn check_rejects_bad_parameters() {
+            let defaults = query(None, None, None).check().unwrap();
+            assert!(defaults.descending);
+            assert_eq!((defaults.offset, defaults.limit), (0, DEFAULT_LIMIT));
+
+            assert!(query(Some("moves"), None, None).check().is_err());
+            assert!(query(None, Some("up"), None).check().is_err());
+            assert!(query(None, None, Some(0)).check().is_err());
+            assert!(query(None, None, Some(MAX_LIMIT + 1)).check().is_err());
+            let unknown = GameQuery { gameType: Some(String::from("Checkers")), ..query(None, None, None) };
+            assert!(unknown.check().is_err());
+        }
+
+        #[test]
+        fn apply_filters_sorts_and_pages() {
+            // game i is played on day i / 2, so dates come in tied pairs
+            let games: Vec<Game> = (0..7)
+                .map(|i| Game {
+                    gameNumber: i.to_string(),
+                    GameDate: i / 2,
+                    ..connect4(if i == 3 { "carol" } else { "alice" }, "bob")
+                })
+                .collect();
+            let numbers = |page: &Page| page.items.iter().map(|game| game.gameNumber.clone()).collect::<Vec<_>>();
+
+            let ascending = GameQuery { offset: Some(2), ..query(Some("date"), Some("asc"), Some(3)) };
+            let page = ascending.check().unwrap().apply(games.clone());
+            assert_eq!((page.total, page.offset, page.limit), (7, 2, 3));
+            assert_eq!(numbers(&page), ["2", "3", "4"]);
+
+            // ties are reversed along with everything else
+            let page = query(None, None, Some(3)).check().unwrap().apply(games.clone());
+            assert_eq!(numbers(&page), ["6", "5", "4"]);
+
+            let carol = GameQuery { player: Some(String::from("carol")), ..query(None, None, None) };
+            let page = carol.check().unwrap().apply(games.clone());
+            assert_eq!((page.total, numbers(&page)), (1, vec![String::from("3")]));
+
+            let range = GameQuery { from: Some(1), to: Some(2), ..query(None, Some("asc"), None) };
+            let page = range.check().unwrap().apply(games);
+            assert_eq!(numbers(&page), ["2", "3", "4", "5"]);
         }
     }
 }
 
-fn rocket() -> Result<rocket::Rocket, mongodb::error::Error> {
-    // create a connection to the games collection
-    let collection = mongodb::Client::with_uri_str("mongodb://localhost:27017/")?
-        .database("Connect4DB")
-        .collection("games");
+mod stats {
+    use std::collections::BTreeMap;
 
-    // create the web server object
-    Ok(rocket::ignite()
-        .manage(collection)
-        .mount("/", routes![
-            index,
-            files,
-            games::get, 
-            games::post,
-        ]))
+    use bson::{Bson, Document};
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::State;
+    use rocket_contrib::json::Json;
+    use serde::{Serialize, Deserialize};
+
+    use crate::games::{ErrorBody, Game, DIFFICULTIES};
+    use crate::store::GameStore;
+
+    const COMPUTER: &str = "Computer";
+    const DRAW: &str = "Draw";
+
+    // games of one type; the stores' aggregations produce these rows directly
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    #[allow(non_snake_case)]
+    pub struct GameTypeSummary {
+        gameType: String,
+        games: usize,
+        computerGames: usize,
+        computerWins: usize,
+        draws: usize,
+    }
+
+    #[derive(Serialize, Debug)]
+    #[allow(non_snake_case)]
+    pub struct Summary {
+        games: usize,
+        computerGames: usize,
+        computerWins: usize,
+        draws: usize,
+        byGameType: Vec<GameTypeSummary>,
+    }
+
+    // the computer's results at one difficulty of one game type
+    #[derive(Serialize, Deserialize, Debug, Default)]
+    #[allow(non_snake_case)]
+    pub struct ComputerRecord {
+        gameType: String,
+        // None for games saved before the difficulty was recorded
+        #[serde(default)]
+        difficulty: Option<String>,
+        games: usize,
+        wins: usize,
+        losses: usize,
+        draws: usize,
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct PlayerWins {
+        name: String,
+        wins: usize,
+    }
+
+    // in-memory versions of the aggregations, for stores without their own
+
+    pub fn summarize(games: &[Game]) -> Vec<GameTypeSummary> {
+        let mut by_type = BTreeMap::new();
+        for game in games {
+            let summary = by_type
+                .entry(game.gameType.clone())
+                .or_insert_with(|| GameTypeSummary { gameType: game.gameType.clone(), ..Default::default() });
+            summary.games += 1;
+            summary.computerGames += (game.Player2Name == COMPUTER) as usize;
+            summary.computerWins += (game.WinnerName == COMPUTER) as usize;
+            summary.draws += (game.WinnerName == DRAW) as usize;
+        }
+        by_type.into_iter().map(|(_, summary)| summary).collect()
+    }
+
+    pub fn computer_records(games: &[Game]) -> Vec<ComputerRecord> {
+        let mut records = BTreeMap::new();
+        for game in games.iter().filter(|game| game.Player2Name == COMPUTER) {
+            let key = (game.gameType.clone(), game.Difficulty.clone());
+            let record = records.entry(key).or_insert_with(|| ComputerRecord {
+                gameType: game.gameType.clone(),
+                difficulty: game.Difficulty.clone(),
+                ..Default::default()
+            });
+            record.games += 1;
+            match game.WinnerName.as_str() {
+                COMPUTER => record.wins += 1,
+                DRAW => record.draws += 1,
+                _ => record.losses += 1,
+            }
+        }
+        let mut records: Vec<ComputerRecord> = records.into_iter().map(|(_, record)| record).collect();
+        sort_records(&mut records);
+        records
+    }
+
+    pub fn player_wins(games: &[Game], game_type: Option<&str>) -> Vec<PlayerWins> {
+        let mut wins = BTreeMap::new();
+        for game in games {
+            if game.WinnerName == DRAW || game_type.map_or(false, |game_type| game.gameType != game_type) {
+                continue;
+            }
+            *wins.entry(game.WinnerName.clone()).or_insert(0) += 1;
+        }
+        let mut wins: Vec<PlayerWins> = wins.into_iter().map(|(name, wins)| PlayerWins { name, wins }).collect();
+        // stable, so equal counts stay in name order
+        wins.sort_by(|a, b| b.wins.cmp(&a.wins));
+        wins
+    }
+
+    // by game type, then from the easiest difficulty up
+    pub fn sort_records(records: &mut Vec<ComputerRecord>) {
+        let rank = |difficulty: &Option<String>| match difficulty {
+            Some(difficulty) => DIFFICULTIES.iter().position(|&known| known == difficulty),
+            None => None,
+        };
+        records.sort_by(|a, b| (&a.gameType, rank(&a.difficulty)).cmp(&(&b.gameType, rank(&b.difficulty))));
+    }
+
+    // 1 for every document where field equals value
+    fn count_equal(field: &str, value: &str) -> Bson {
+        Bson::Document(doc! { "$sum": { "$cond": [{ "$eq": [field, value] }, 1, 0] } })
+    }
+
+    // MongoDB aggregation pipelines producing the same rows
+
+    pub fn summary_pipeline() -> Vec<Document> {
+        vec![
+            doc! { "$group": {
+                "_id": "$gameType",
+                "games": { "$sum": 1 },
+                "computerGames": count_equal("$Player2Name", COMPUTER),
+                "computerWins": count_equal("$WinnerName", COMPUTER),
+                "draws": count_equal("$WinnerName", DRAW),
+            } },
+            doc! { "$project": {
+                "_id": 0,
+                "gameType": "$_id",
+                "games": 1,
+                "computerGames": 1,
+                "computerWins": 1,
+                "draws": 1,
+            } },
+            doc! { "$sort": { "gameType": 1 } },
+        ]
+    }
+
+    pub fn computer_pipeline() -> Vec<Document> {
+        vec![
+            doc! { "$match": { "Player2Name": COMPUTER } },
+            doc! { "$group": {
+                "_id": { "gameType": "$gameType", "difficulty": "$Difficulty" },
+                "games": { "$sum": 1 },
+                "wins": count_equal("$WinnerName", COMPUTER),
+                "draws": count_equal("$WinnerName", DRAW),
+            } },
+            doc! { "$project": {
+                "_id": 0,
+                "gameType": "$_id.gameType",
+                "difficulty": "$_id.difficulty",
+                "games": 1,
+                "wins": 1,
+                "draws": 1,
+                "losses": { "$subtract": ["$games", { "$add": ["$wins", "$draws"] }] },
+            } },
+        ]
+    }
+
+    pub fn players_pipeline(game_type: Option<&str>) -> Vec<Document> {
+        let mut filter = doc! { "WinnerName": { "$ne": DRAW } };
+        if let Some(game_type) = game_type {
+            filter.insert("gameType", game_type);
+        }
+        vec![
+            doc! { "$match": filter },
+            doc! { "$group": { "_id": "$WinnerName", "wins": { "$sum": 1 } } },
+            doc! { "$project": { "_id": 0, "name": "$_id", "wins": 1 } },
+            doc! { "$sort": { "wins": -1, "name": 1 } },
+        ]
+    }
+
+    // totals over every game, and per game type
+    #[get("/stats/summary")]
+    pub fn summary(store: State<Box<dyn GameStore>>) -> Result<Json<Summary>, status::Custom<Json<ErrorBody>>> {
+        let by_type = store
+            .summary()
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+        Ok(Json(Summary {
+            games: by_type.iter().map(|summary| summary.games).sum(),
+            computerGames: by_type.iter().map(|summary| summary.computerGames).sum(),
+            computerWins: by_type.iter().map(|summary| summary.computerWins).sum(),
+            draws: by_type.iter().map(|summary| summary.draws).sum(),
+            byGameType: by_type,
+        }))
+    }
+
+    // how the computer does at each difficulty
+    #[get("/stats/computer")]
+    pub fn computer(store: State<Box<dyn GameStore>>) -> Result<Json<Vec<ComputerRecord>>, status::Custom<Json<ErrorBody>>> {
+        store
+            .computer_records()
+            .map(Json)
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))
+    }
+
+    // wins per player, most first, optionally for one game type
+    #[get("/stats/players?<gameType>")]
+    #[allow(non_snake_case)]
+    pub fn players(gameType: Option<String>, store: State<Box<dyn GameStore>>) -> Result<Json<Vec<PlayerWins>>, status::Custom<Json<ErrorBody>>> {
+        store
+            .player_wins(gameType.as_ref().map(String::as_str))
+            .map(Json)
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))
+    }
 }
 
-fn main() {
-    // launch server or report error
-    match rocket() {
-        Ok(rocket) => {
-            let error = rocket.launch();
-            eprintln!("Failed to launch server: {}", error);
+mod ratings {
+    use std::collections::HashMap;
+
+    use engine::GameKind;
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::State;
+    use rocket_contrib::json::Json;
+    use serde::Serialize;
+
+    use crate::games::{ErrorBody, Game};
+    use crate::store::GameStore;
+
+    // Elo ratings, every player starts at INITIAL and moves at most K points a game
+    const INITIAL: f64 = 1200.0;
+    const K: f64 = 32.0;
+
+    // the computer keeps a fixed rating at each difficulty
+    fn computer_rating(difficulty: Option<&str>) -> f64 {
+        match difficulty {
+            Some("Easy") => 1000.0,
+            Some("Medium") => 1300.0,
+            Some("Hard") => 1600.0,
+            Some("Perfect") => 2000.0,
+            _ => INITIAL,
+        }
+    }
+
+    // a player's rating after one of their games
+    #[derive(Serialize, Debug, Clone)]
+    #[allow(non_snake_case)]
+    pub struct RatingPoint {
+        GameDate: i64,
+        rating: i64,
+    }
+
+    #[derive(Serialize, Debug, Clone)]
+    #[allow(non_snake_case)]
+    pub struct Rating {
+        name: String,
+        gameType: String,
+        rating: i64,
+        games: usize,
+        wins: usize,
+        draws: usize,
+        losses: usize,
+        history: Vec<RatingPoint>,
+        // the rating the player started from, for clients working out changes
+        initial: i64,
+        #[serde(skip)]
+        exact: f64,
+    }
+
+    impl Rating {
+        fn new(name: &str, game_type: &str) -> Rating {
+            Rating {
+                name: name.to_string(),
+                gameType: game_type.to_string(),
+                rating: INITIAL as i64,
+                games: 0,
+                wins: 0,
+                draws: 0,
+                losses: 0,
+                history: Vec::new(),
+                initial: INITIAL as i64,
+                exact: INITIAL,
+            }
+        }
+
+        // score is 1 for a win, 0.5 for a draw and 0 for a loss
+        fn record(&mut self, opponent: f64, score: f64, date: i64) {
+            let expected = 1.0 / (1.0 + 10f64.powf((opponent - self.exact) / 400.0));
+            self.exact += K * (score - expected);
+            self.rating = self.exact.round() as i64;
+            self.games += 1;
+            if score == 1.0 {
+                self.wins += 1;
+            } else if score == 0.0 {
+                self.losses += 1;
+            } else {
+                self.draws += 1;
+            }
+            self.history.push(RatingPoint { GameDate: date, rating: self.rating });
         }
-        Err(error) => eprintln!("Failed to create server: {}", error),
     }
+
+    // replays every game in the order they were played, ratings are kept per game type
+    pub fn rate(mut games: Vec<Game>) -> Vec<Rating> {
+        games.sort_by_key(|game| game.GameDate);
+
+        let mut ratings: HashMap<(String, String), Rating> = HashMap::new();
+        for game in &games {
+            let (player1, player2) = (&game.Player1Name, &game.Player2Name);
+            if player1 == player2 {
+                continue;
+            }
+            let score = if game.WinnerName == *player1 {
+                1.0
+            } else if game.WinnerName == *player2 {
+                0.0
+            } else {
+                0.5
+            };
+
+            let key = |name: &String| (game.gameType.clone(), name.clone());
+            let rating1 = ratings
+                .get(&key(player1))
+                .map_or(INITIAL, |rating| rating.exact);
+            let rating2 = if player2 == "Computer" {
+                computer_rating(game.Difficulty.as_ref().map(String::as_str))
+            } else {
+                ratings.get(&key(player2)).map_or(INITIAL, |rating| rating.exact)
+            };
+
+            // both players are rated on what they had before the game
+            ratings
+                .entry(key(player1))
+                .or_insert_with(|| Rating::new(player1, &game.gameType))
+                .record(rating2, score, game.GameDate);
+            if player2 != "Computer" {
+                ratings
+                    .entry(key(player2))
+                    .or_insert_with(|| Rating::new(player2, &game.gameType))
+                    .record(rating1, 1.0 - score, game.GameDate);
+            }
+        }
+
+        let mut ratings: Vec<Rating> = ratings.into_iter().map(|(_, rating)| rating).collect();
+        ratings.sort_by(|a, b| {
+            (&a.gameType, b.rating, &a.name).cmp(&(&b.gameType, a.rating, &b.name))
+        });
+        ratings
+    }
+
+    // everyone's rating and how it got there, highest first within each game type
+    #[get("/ratings?<gameType>")]
+    #[allow(non_snake_case)]
+    pub fn get(gameType: Option<String>, store: State<Box<dyn GameStore>>) -> Result<Json<Vec<Rating>>, status::Custom<Json<ErrorBody>>> {
+        if let Some(game_type) = &gameType {
+            game_type
+                .parse::<GameKind>()
+                .map_err(|error| ErrorBody::new(Status::UnprocessableEntity, error))?;
+        }
+        let games = store
+            .all()
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+
+        let mut ratings = rate(games);
+        if let Some(game_type) = gameType {
+            ratings.retain(|rating| rating.gameType == game_type);
+        }
+        Ok(Json(ratings))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn game(player1: &str, player2: &str, winner: &str, date: i64) -> Game {
+            Game {
+                gameNumber: String::new(),
+                gameType: String::from("Connect-4"),
+                Player1Name: player1.to_string(),
+                Player2Name: player2.to_string(),
+                WinnerName: winner.to_string(),
+                GameDate: date,
+                Player1Inventory: None,
+                Player2Inventory: None,
+                Moves: Vec::new(),
+                UsedUndo: false,
+                Difficulty: None,
+                ClientId: None,
+                Variant: None,
+            }
+        }
+
+        fn rating<'a>(ratings: &'a [Rating], name: &str) -> &'a Rating {
+            ratings.iter().find(|rating| rating.name == name).unwrap()
+        }
+
+        #[test]
+        fn even_players_trade_half_of_k() {
+            let ratings = rate(vec![game("alice", "bob", "alice", 1)]);
+            assert_eq!(rating(&ratings, "alice").rating, 1216);
+            assert_eq!(rating(&ratings, "bob").rating, 1184);
+            assert_eq!(ratings[0].name, "alice");
+
+            let ratings = rate(vec![game("alice", "bob", "Draw", 1)]);
+            assert_eq!((rating(&ratings, "alice").rating, rating(&ratings, "alice").draws), (1200, 1));
+        }
+
+        #[test]
+        fn games_are_rated_in_date_order_on_ratings_before_the_game() {
+            // bob beats carol after losing to alice, whatever order they were saved in
+            let ratings = rate(vec![game("bob", "carol", "bob", 2), game("alice", "bob", "alice", 1)]);
+            let bob = rating(&ratings, "bob");
+            assert_eq!((bob.wins, bob.losses), (1, 1));
+            assert_eq!(bob.history.iter().map(|point| point.rating).collect::<Vec<_>>(), [1184, 1201]);
+            assert_eq!(rating(&ratings, "carol").rating, 1183);
+        }
+
+        #[test]
+        fn computer_has_a_fixed_rating_per_difficulty() {
+            let mut hard = game("alice", "Computer", "Computer", 1);
+            hard.Difficulty = Some(String::from("Hard"));
+            let ratings = rate(vec![hard, game("alice", "alice", "alice", 2)]);
+            // losing to a 1600 costs little, the computer and games against yourself aren't rated
+            assert_eq!(ratings.len(), 1);
+            assert_eq!(rating(&ratings, "alice").rating, 1197);
+            assert_eq!(rating(&ratings, "alice").games, 1);
+        }
+    }
+}
+
+mod players {
+    use std::collections::BTreeMap;
+
+    use rocket::http::Status;
+    use rocket::response::status;
+    use rocket::State;
+    use rocket_contrib::json::Json;
+    use serde::Serialize;
+
+    use crate::games::{ErrorBody, Game, DIFFICULTIES};
+    use crate::store::GameStore;
+
+    const COMPUTER: &str = "Computer";
+    const DRAW: &str = "Draw";
+
+    // results from the player's side
+    #[derive(Serialize, Debug, Default, Clone, Copy)]
+    pub struct Record {
+        games: usize,
+        wins: usize,
+        losses: usize,
+        draws: usize,
+    }
+
+    impl Record {
+        fn add(&mut self, outcome: Outcome) {
+            self.games += 1;
+            match outcome {
+                Outcome::Win => self.wins += 1,
+                Outcome::Loss => self.losses += 1,
+                Outcome::Draw => self.draws += 1,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Outcome {
+        Win,
+        Loss,
+        Draw,
+    }
+
+    #[derive(Serialize, Debug)]
+    #[allow(non_snake_case)]
+    pub struct GameTypeRecord {
+        gameType: String,
+        #[serde(flatten)]
+        record: Record,
+    }
+
+    #[derive(Serialize, Debug)]
+    #[allow(non_snake_case)]
+    pub struct DifficultyRecord {
+        gameType: String,
+        // None for games saved before the difficulty was recorded
+        difficulty: Option<String>,
+        #[serde(flatten)]
+        record: Record,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct OpponentRecord {
+        opponent: String,
+        #[serde(flatten)]
+        record: Record,
+    }
+
+    // GET /players/<name>, everything the profile page shows besides the games themselves
+    #[derive(Serialize, Debug)]
+    #[allow(non_snake_case)]
+    pub struct Profile {
+        name: String,
+        #[serde(flatten)]
+        record: Record,
+        byGameType: Vec<GameTypeRecord>,
+        vsComputer: Vec<DifficultyRecord>,
+        // other humans, most games first
+        headToHead: Vec<OpponentRecord>,
+        longestWinStreak: usize,
+        longestLossStreak: usize,
+    }
+
+    // name's record over games, None when they haven't played any
+    pub fn profile(name: &str, mut games: Vec<Game>) -> Option<Profile> {
+        games.sort_by_key(|game| game.GameDate);
+
+        let mut record = Record::default();
+        let mut by_type = BTreeMap::new();
+        let mut vs_computer = BTreeMap::new();
+        let mut head_to_head = BTreeMap::new();
+        let (mut longest_wins, mut longest_losses) = (0, 0);
+        let mut streak: Option<(Outcome, usize)> = None;
+
+        for game in &games {
+            let opponent = if game.Player1Name == name {
+                &game.Player2Name
+            } else if game.Player2Name == name {
+                &game.Player1Name
+            } else {
+                continue;
+            };
+            // games against themselves say nothing about the player
+            if opponent == name {
+                continue;
+            }
+            let outcome = if game.WinnerName == name {
+                Outcome::Win
+            } else if game.WinnerName == DRAW {
+                Outcome::Draw
+            } else {
+                Outcome::Loss
+            };
+
+            record.add(outcome);
+            by_type
+                .entry(game.gameType.clone())
+                .or_insert_with(Record::default)
+                .add(outcome);
+            if opponent == COMPUTER {
+                // from the easiest difficulty up, unknown last
+                let rank = game
+                    .Difficulty
+                    .as_ref()
+                    .and_then(|difficulty| DIFFICULTIES.iter().position(|known| known == difficulty))
+                    .unwrap_or(DIFFICULTIES.len());
+                vs_computer
+                    .entry((game.gameType.clone(), rank, game.Difficulty.clone()))
+                    .or_insert_with(Record::default)
+                    .add(outcome);
+            } else {
+                head_to_head
+                    .entry(opponent.clone())
+                    .or_insert_with(Record::default)
+                    .add(outcome);
+            }
+
+            let length = match streak {
+                Some((last, length)) if last == outcome => length + 1,
+                _ => 1,
+            };
+            streak = Some((outcome, length));
+            match outcome {
+                Outcome::Win => longest_wins = longest_wins.max(length),
+                Outcome::Loss => longest_losses = longest_losses.max(length),
+                Outcome::Draw => (),
+            }
+        }
+
+        if record.games == 0 {
+            return None;
+        }
+        let mut head_to_head: Vec<OpponentRecord> = head_to_head
+            .into_iter()
+            .map(|(opponent, record)| OpponentRecord { opponent, record })
+            .collect();
+        // stable, so equal counts stay in name order
+        head_to_head.sort_by(|a, b| b.record.games.cmp(&a.record.games));
+
+        Some(Profile {
+            name: name.to_string(),
+            record,
+            byGameType: by_type
+                .into_iter()
+                .map(|(game_type, record)| GameTypeRecord { gameType: game_type, record })
+                .collect(),
+            vsComputer: vs_computer
+                .into_iter()
+                .map(|((game_type, _, difficulty), record)| DifficultyRecord { gameType: game_type, difficulty, record })
+                .collect(),
+            headToHead: head_to_head,
+            longestWinStreak: longest_wins,
+            longestLossStreak: longest_losses,
+        })
+    }
+
+    #[get("/players/<name>")]
+    pub fn get(name: String, store: State<Box<dyn GameStore>>) -> Result<Json<Profile>, status::Custom<Json<ErrorBody>>> {
+        let games = store
+            .player_games(&name)
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+        match profile(&name, games) {
+            Some(profile) => Ok(Json(profile)),
+            None => Err(ErrorBody::new(Status::NotFound, format!("no games recorded for {}", name))),
+        }
+    }
+}
+
+mod rooms {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use engine::{GameKind, GameState, Move, Player};
+    use ring::rand::{SecureRandom, SystemRandom};
+    use rocket::http::Status;
+    use rocket::State;
+    use rocket_contrib::json::Json;
+    use serde::{Serialize, Deserialize};
+
+    use crate::accounts::{self, LoggedIn};
+    use crate::games::Game;
+    use crate::store::{GameStore, UserStore};
+
+    // letters for room codes, without the easily confused 0/O and 1/I
+    const CODE_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    const CODE_LENGTH: usize = 6;
+
+    // random bytes in a seat token
+    const TOKEN_BYTES: usize = 24;
+
+    // rooms nobody has joined or moved in for this long are dropped
+    const IDLE_LIMIT: Duration = Duration::from_secs(60 * 60);
+    // a finished room is kept just long enough for the opponent's next poll to see
+    // the last move, its result is already saved
+    const FINISHED_LIMIT: Duration = Duration::from_secs(60);
+
+    // a player's place in a room, the token proves which browser holds it
+    struct Seat {
+        name: String,
+        token: String,
+    }
+
+    // an online game refereed by the server, Player::One created the room
+    struct Room {
+        state: GameState,
+        seats: [Option<Seat>; 2],
+        // when a player last created, joined or moved in the room
+        last_active: Instant,
+    }
+
+    // open rooms keyed by their join code
+    #[derive(Default)]
+    pub struct Rooms(Mutex<HashMap<String, Room>>);
+
+    #[derive(Deserialize)]
+    #[allow(non_snake_case)]
+    pub struct NewRoom {
+        gameType: String,
+        PlayerName: String,
+    }
+
+    #[derive(Deserialize)]
+    #[allow(non_snake_case)]
+    pub struct JoinRoom {
+        PlayerName: String,
+    }
+
+    #[derive(Deserialize)]
+    #[allow(non_snake_case)]
+    pub struct PlayMove {
+        Token: String,
+        Move: Move,
+    }
+
+    // what clients see of a room; Seat and Token only go to the player taking the seat
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    pub struct RoomView {
+        roomCode: String,
+        gameType: String,
+        Player1Name: Option<String>,
+        Player2Name: Option<String>,
+        Moves: Vec<Move>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        Seat: Option<u8>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        Token: Option<String>,
+    }
+
+    impl Room {
+        fn view(&self, code: &str, seat: Option<(u8, String)>) -> RoomView {
+            let name = |index: usize| self.seats[index].as_ref().map(|seat| seat.name.clone());
+            let (seat, token) = match seat {
+                Some((seat, token)) => (Some(seat), Some(token)),
+                None => (None, None),
+            };
+            RoomView {
+                roomCode: code.to_string(),
+                gameType: self.state.kind().to_string(),
+                Player1Name: name(0),
+                Player2Name: name(1),
+                Moves: self.state.history().to_vec(),
+                Seat: seat,
+                Token: token,
+            }
+        }
+
+        fn expired(&self, now: Instant) -> bool {
+            let limit = if self.state.is_over() { FINISHED_LIMIT } else { IDLE_LIMIT };
+            now.duration_since(self.last_active) > limit
+        }
+
+        // the player holding token, None for anyone else
+        fn player(&self, token: &str) -> Option<Player> {
+            let holds = |index: usize| match &self.seats[index] {
+                Some(seat) => seat.token == token,
+                None => false,
+            };
+            if holds(0) {
+                Some(Player::One)
+            } else if holds(1) {
+                Some(Player::Two)
+            } else {
+                None
+            }
+        }
+    }
+
+    // drops finished and abandoned rooms, checked whenever a room is created or polled
+    fn expire(rooms: &mut HashMap<String, Room>) {
+        let now = Instant::now();
+        rooms.retain(|_, room| !room.expired(now));
+    }
+
+    // codes and tokens come from the system's secure generator, a token is all it
+    // takes to move for a player
+    fn fill_random(bytes: &mut [u8]) -> Result<(), Status> {
+        SystemRandom::new().fill(bytes).map_err(|_| Status::InternalServerError)
+    }
+
+    fn new_code() -> Result<String, Status> {
+        let mut bytes = [0u8; CODE_LENGTH];
+        fill_random(&mut bytes)?;
+        // 256 is a multiple of the 32 letters, so each is equally likely
+        Ok(bytes
+            .iter()
+            .map(|&byte| CODE_LETTERS[byte as usize % CODE_LETTERS.len()] as char)
+            .collect())
+    }
+
+    fn new_token() -> Result<String, Status> {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        fill_random(&mut bytes)?;
+        Ok(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD))
+    }
+
+    #[post("/rooms", format="json", data="<new>")]
+    pub fn create(
+        new: Json<NewRoom>,
+        user: Option<LoggedIn>,
+        rooms: State<Rooms>,
+        users: State<Box<dyn UserStore>>,
+    ) -> Result<Json<RoomView>, Status> {
+        let new = new.into_inner();
+        let kind = new.gameType.parse::<GameKind>().map_err(|_| Status::UnprocessableEntity)?;
+        // room games are saved without being posted, so the names are checked here
+        if accounts::is_reserved(&new.PlayerName) {
+            return Err(Status::UnprocessableEntity);
+        }
+        let name = accounts::claim(&new.PlayerName, &user, &**users).map_err(|_| Status::Forbidden)?;
+
+        let mut rooms = rooms.0.lock().unwrap();
+        expire(&mut rooms);
+        let mut code = new_code()?;
+        while rooms.contains_key(&code) {
+            code = new_code()?;
+        }
+
+        let token = new_token()?;
+        let room = Room {
+            state: GameState::new(kind),
+            seats: [Some(Seat { name, token: token.clone() }), None],
+            last_active: Instant::now(),
+        };
+        let view = room.view(&code, Some((1, token)));
+        rooms.insert(code, room);
+        Ok(Json(view))
+    }
+
+    #[post("/rooms/<code>/join", format="json", data="<join>")]
+    pub fn join(
+        code: String,
+        join: Json<JoinRoom>,
+        user: Option<LoggedIn>,
+        rooms: State<Rooms>,
+        users: State<Box<dyn UserStore>>,
+    ) -> Result<Json<RoomView>, Status> {
+        if accounts::is_reserved(&join.PlayerName) {
+            return Err(Status::UnprocessableEntity);
+        }
+        let name = accounts::claim(&join.PlayerName, &user, &**users).map_err(|_| Status::Forbidden)?;
+        let mut rooms = rooms.0.lock().unwrap();
+        let room = rooms.get_mut(&code).ok_or(Status::NotFound)?;
+        if room.seats[1].is_some() {
+            return Err(Status::Conflict);
+        }
+
+        let token = new_token()?;
+        room.seats[1] = Some(Seat { name, token: token.clone() });
+        room.last_active = Instant::now();
+        Ok(Json(room.view(&code, Some((2, token)))))
+    }
+
+    // polled by both players to pick up the opponent's moves
+    #[get("/rooms/<code>")]
+    pub fn get(code: String, rooms: State<Rooms>) -> Option<Json<RoomView>> {
+        let mut rooms = rooms.0.lock().unwrap();
+        expire(&mut rooms);
+        rooms.get(&code).map(|room| Json(room.view(&code, None)))
+    }
+
+    #[post("/rooms/<code>/moves", format="json", data="<play>")]
+    pub fn play(
+        code: String,
+        play: Json<PlayMove>,
+        rooms: State<Rooms>,
+        store: State<Box<dyn GameStore>>,
+    ) -> Result<Json<RoomView>, Status> {
+        let mut rooms = rooms.0.lock().unwrap();
+        let room = rooms.get_mut(&code).ok_or(Status::NotFound)?;
+        let player = room.player(&play.Token).ok_or(Status::Forbidden)?;
+
+        // no moves until both seats are taken, and only on the player's own turn
+        if room.seats[1].is_none() || player != room.state.to_move() {
+            return Err(Status::Conflict);
+        }
+        room.state.apply_move(play.Move).map_err(|_| Status::UnprocessableEntity)?;
+        room.last_active = Instant::now();
+
+        // the server saw every move, so it records the result rather than either player
+        if room.state.is_over() {
+            let name = |index: usize| room.seats[index].as_ref().unwrap().name.clone();
+            let game = Game::finished(&room.state, &name(0), &name(1));
+            if let Err(error) = store.insert(&game) {
+                eprintln!("Failed to save online game: {}", error);
+            }
+        }
+        Ok(Json(room.view(&code, None)))
+    }
+}
+
+mod accounts {
+    use std::num::NonZeroU32;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use ring::pbkdf2;
+    use ring::rand::{SecureRandom, SystemRandom};
+    use rocket::http::{Cookie, Cookies, Status};
+    use rocket::outcome::IntoOutcome;
+    use rocket::request::{self, FromRequest, Request};
+    use rocket::response::status;
+    use rocket::State;
+    use rocket_contrib::json::Json;
+    use serde::{Serialize, Deserialize};
+
+    use crate::games::ErrorBody;
+    use crate::store::UserStore;
+
+    // private cookie holding the logged in player's name
+    const COOKIE: &str = "user";
+    const ITERATIONS: u32 = 100_000;
+    const MIN_PASSWORD: usize = 8;
+    // names the server writes into game records itself
+    const RESERVED: [&str; 2] = ["Computer", "Draw"];
+
+    // a registered player, only a salted hash of the password is kept
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub struct User {
+        pub name: String,
+        // lowercase name, what accounts are looked up by
+        pub key: String,
+        pub salt: String,
+        pub hash: String,
+        pub created: i64,
+    }
+
+    impl User {
+        fn new(name: &str, password: &str) -> Result<User, String> {
+            let mut salt = [0u8; 16];
+            SystemRandom::new()
+                .fill(&mut salt)
+                .map_err(|_| String::from("no randomness for a password salt"))?;
+            let mut hash = [0u8; 32];
+            pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations(), &salt, password.as_bytes(), &mut hash);
+            let created = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as i64)
+                .unwrap_or(0);
+            Ok(User {
+                name: name.to_string(),
+                key: key(name),
+                salt: base64::encode(&salt),
+                hash: base64::encode(&hash),
+                created,
+            })
+        }
+
+        fn check_password(&self, password: &str) -> bool {
+            match (base64::decode(&self.salt), base64::decode(&self.hash)) {
+                (Ok(salt), Ok(hash)) => {
+                    pbkdf2::verify(pbkdf2::PBKDF2_HMAC_SHA256, iterations(), &salt, password.as_bytes(), &hash).is_ok()
+                }
+                _ => false,
+            }
+        }
+    }
+
+    fn iterations() -> NonZeroU32 {
+        NonZeroU32::new(ITERATIONS).unwrap()
+    }
+
+    pub fn key(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+
+    // whether name is, up to case and surrounding spaces, one the server writes itself
+    pub fn is_reserved(name: &str) -> bool {
+        RESERVED.iter().any(|reserved| reserved.eq_ignore_ascii_case(name.trim()))
+    }
+
+    fn check_name(name: &str) -> Result<(), String> {
+        let length = name.chars().count();
+        if length < 3 || length > 20 {
+            return Err(String::from("name must be 3 to 20 characters"));
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '_' || c == '-') {
+            return Err(String::from("name may only use letters, digits, spaces, _ and -"));
+        }
+        if is_reserved(name) {
+            return Err(format!("{} is reserved", name));
+        }
+        Ok(())
+    }
+
+    // the player a request's session cookie belongs to
+    pub struct LoggedIn(pub String);
+
+    impl<'a, 'r> FromRequest<'a, 'r> for LoggedIn {
+        type Error = ();
+
+        fn from_request(request: &'a Request<'r>) -> request::Outcome<LoggedIn, ()> {
+            request
+                .cookies()
+                .get_private(COOKIE)
+                .map(|cookie| LoggedIn(cookie.value().to_string()))
+                .or_forward(())
+        }
+    }
+
+    // registered names may only be played under by whoever is logged in as them; returns
+    // the name to record, the account's own spelling for a registered player so their
+    // games aren't split across "Bob" and " bob", guest names as given
+    pub fn claim(name: &str, user: &Option<LoggedIn>, users: &dyn UserStore) -> Result<String, String> {
+        let account = match users.user(&key(name))? {
+            Some(account) => account,
+            None => return Ok(name.to_string()),
+        };
+        match user {
+            Some(LoggedIn(logged_in)) if key(logged_in) == account.key => Ok(account.name),
+            _ => Err(format!("{} is a registered player, log in to play as them", account.name)),
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct Credentials {
+        name: String,
+        password: String,
+    }
+
+    // the logged in player, all the frontend needs to know
+    #[derive(Serialize)]
+    pub struct Account {
+        name: String,
+    }
+
+    #[post("/accounts", format="json", data="<credentials>")]
+    pub fn register(
+        credentials: Json<Credentials>,
+        users: State<Box<dyn UserStore>>,
+        mut cookies: Cookies,
+    ) -> Result<status::Created<Json<Account>>, status::Custom<Json<ErrorBody>>> {
+        let name = credentials.name.trim();
+        check_name(name).map_err(|error| ErrorBody::new(Status::UnprocessableEntity, error))?;
+        if credentials.password.chars().count() < MIN_PASSWORD {
+            let error = format!("password must be at least {} characters", MIN_PASSWORD);
+            return Err(ErrorBody::new(Status::UnprocessableEntity, error));
+        }
+
+        let user = User::new(name, &credentials.password)
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+        match users.insert_user(&user) {
+            Ok(true) => {
+                cookies.add_private(Cookie::new(COOKIE, user.name.clone()));
+                let account = Account { name: user.name };
+                Ok(status::Created(String::from("/session"), Some(Json(account))))
+            }
+            Ok(false) => Err(ErrorBody::new(Status::Conflict, format!("{} is already taken", name))),
+            Err(error) => Err(ErrorBody::new(Status::InternalServerError, error)),
+        }
+    }
+
+    #[post("/session", format="json", data="<credentials>")]
+    pub fn login(
+        credentials: Json<Credentials>,
+        users: State<Box<dyn UserStore>>,
+        mut cookies: Cookies,
+    ) -> Result<Json<Account>, status::Custom<Json<ErrorBody>>> {
+        let user = users
+            .user(&key(&credentials.name))
+            .map_err(|error| ErrorBody::new(Status::InternalServerError, error))?;
+        match user {
+            Some(user) if user.check_password(&credentials.password) => {
+                cookies.add_private(Cookie::new(COOKIE, user.name.clone()));
+                Ok(Json(Account { name: user.name }))
+            }
+            _ => Err(ErrorBody::new(Status::Unauthorized, String::from("wrong name or password"))),
+        }
+    }
+
+    #[get("/session")]
+    pub fn current(user: Option<LoggedIn>) -> Result<Json<Account>, Status> {
+        match user {
+            Some(LoggedIn(name)) => Ok(Json(Account { name })),
+            None => Err(Status::Unauthorized),
+        }
+    }
+
+    #[delete("/session")]
+    pub fn logout(mut cookies: Cookies) -> status::NoContent {
+        cookies.remove_private(Cookie::named(COOKIE));
+        status::NoContent
+    }
+
+    #[cfg(test)]
+    pub mod tests {
+        use super::*;
+
+        // registered players without passwords, enough to claim names against
+        pub struct Users(pub Vec<User>);
+
+        impl Users {
+            pub fn new(names: &[&str]) -> Users {
+                Users(
+                    names
+                        .iter()
+                        .map(|&name| User {
+                            name: name.to_string(),
+                            key: key(name),
+                            salt: String::new(),
+                            hash: String::new(),
+                            created: 0,
+                        })
+                        .collect(),
+                )
+            }
+        }
+
+        impl UserStore for Users {
+            fn user(&self, key: &str) -> Result<Option<User>, String> {
+                Ok(self.0.iter().find(|user| user.key == key).cloned())
+            }
+
+            fn insert_user(&self, _: &User) -> Result<bool, String> {
+                Ok(false)
+            }
+        }
+
+        #[test]
+        fn claim_gives_registered_names_their_own_spelling() {
+            let users = Users::new(&["Bob"]);
+            let bob = Some(LoggedIn(String::from("Bob")));
+            assert_eq!(claim(" BOB", &bob, &users), Ok(String::from("Bob")));
+            assert_eq!(claim("bob", &bob, &users), Ok(String::from("Bob")));
+            // guests keep the name they typed
+            assert_eq!(claim("alice", &bob, &users), Ok(String::from("alice")));
+            assert_eq!(claim("alice", &None, &users), Ok(String::from("alice")));
+        }
+
+        #[test]
+        fn claim_keeps_registered_names_for_their_owner() {
+            let users = Users::new(&["Bob", "carol"]);
+            assert!(claim("bob", &None, &users).is_err());
+            assert!(claim("bob", &Some(LoggedIn(String::from("carol"))), &users).is_err());
+        }
+
+        #[test]
+        fn reserved_names_ignore_case_and_spaces() {
+            assert!(is_reserved("Computer"));
+            assert!(is_reserved(" draw "));
+            assert!(!is_reserved("Computers"));
+            assert!(check_name("COMPUTER").is_err());
+        }
+    }
+}
+
+mod store {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use bson::oid::ObjectId;
+    use bson::{Bson, Document};
+    use mongodb::error::{ErrorKind, WriteFailure};
+    use mongodb::options::FindOptions;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use mongodb::Collection;
+
+    use crate::accounts::User;
+    use crate::config::StoreSettings;
+    use crate::games::{Game, Page, Query};
+    use crate::stats::{self, ComputerRecord, GameTypeSummary, PlayerWins};
+
+    // where finished games are kept
+    pub trait GameStore: Send + Sync {
+        fn all(&self) -> Result<Vec<Game>, String>;
+
+        // stores game under a new id, which is returned and kept as its gameNumber; a game
+        // whose ClientId is already stored isn't stored again, the stored game's id is returned
+        fn insert(&self, game: &Game) -> Result<String, String>;
+
+        // Ok(false) when there's no game with id
+        fn delete(&self, id: &str) -> Result<bool, String>;
+
+        fn get(&self, id: &str) -> Result<Option<Game>, String> {
+            Ok(self.all()?.into_iter().find(|game| game.gameNumber == id))
+        }
+
+        fn by_client_id(&self, client_id: &str) -> Result<Option<Game>, String> {
+            Ok(self
+                .all()?
+                .into_iter()
+                .find(|game| game.ClientId.as_ref().map(String::as_str) == Some(client_id)))
+        }
+
+        // one page of the games matching query
+        fn find(&self, query: &Query) -> Result<Page, String> {
+            Ok(query.apply(self.all()?))
+        }
+
+        fn summary(&self) -> Result<Vec<GameTypeSummary>, String> {
+            Ok(stats::summarize(&self.all()?))
+        }
+
+        fn computer_records(&self) -> Result<Vec<ComputerRecord>, String> {
+            Ok(stats::computer_records(&self.all()?))
+        }
+
+        fn player_wins(&self, game_type: Option<&str>) -> Result<Vec<PlayerWins>, String> {
+            Ok(stats::player_wins(&self.all()?, game_type))
+        }
+
+        // every game name played in
+        fn player_games(&self, name: &str) -> Result<Vec<Game>, String> {
+            let mut games = self.all()?;
+            games.retain(|game| game.Player1Name == name || game.Player2Name == name);
+            Ok(games)
+        }
+    }
+
+    // the games collection of a MongoDB server
+    pub struct MongoStore {
+        collection: Collection,
+    }
+
+    impl MongoStore {
+        // runs an aggregation whose output documents deserialize as T
+        fn aggregate<T: DeserializeOwned>(&self, pipeline: Vec<Document>) -> Result<Vec<T>, String> {
+            let cursor = self.collection.aggregate(pipeline, None).map_err(|error| error.to_string())?;
+            let mut rows = Vec::new();
+            for document in cursor {
+                let document = document.map_err(|error| error.to_string())?;
+                rows.push(bson::from_bson(Bson::Document(document)).map_err(|error| error.to_string())?);
+            }
+            Ok(rows)
+        }
+
+        // a stored game, documents saved before games had ids go by their _id; documents
+        // that can't be converted from BSON are skipped
+        fn game(document: Document) -> Option<Game> {
+            let id = document.get_object_id("_id").ok().map(ObjectId::to_hex);
+            let mut game: Game = bson::from_bson(Bson::Document(document)).ok()?;
+            if game.gameNumber.is_empty() {
+                game.gameNumber = id.unwrap_or_default();
+            }
+            Some(game)
+        }
+
+        // a game is stored once per ClientId; games without one, which are saved with a
+        // null ClientId, are left out of the index
+        pub fn connect(uri: &str, database: &str, collection: &str) -> Result<MongoStore, mongodb::error::Error> {
+            let database = mongodb::Client::with_uri_str(uri)?.database(database);
+            database.run_command(
+                doc! {
+                    "createIndexes": collection,
+                    "indexes": [{
+                        "key": { "ClientId": 1 },
+                        "name": "ClientId_unique",
+                        "unique": true,
+                        "partialFilterExpression": { "ClientId": { "$type": "string" } },
+                    }],
+                },
+                None,
+            )?;
+            Ok(MongoStore { collection: database.collection(collection) })
+        }
+    }
+
+    // the server refused a document because it repeats a unique index key
+    fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+        match &*error.kind {
+            ErrorKind::WriteError(WriteFailure::WriteError(error)) => error.code == 11000,
+            _ => false,
+        }
+    }
+
+    impl GameStore for MongoStore {
+        fn all(&self) -> Result<Vec<Game>, String> {
+            let cursor = self.collection.find(doc!{}, None).map_err(|error| error.to_string())?;
+            Ok(cursor.filter_map(Result::ok).filter_map(MongoStore::game).collect())
+        }
+
+        // the document's _id is the same ObjectId as its gameNumber
+        fn insert(&self, game: &Game) -> Result<String, String> {
+            let id = ObjectId::new().map_err(|error| error.to_string())?;
+            let mut game = game.clone();
+            game.gameNumber = id.to_hex();
+            match bson::to_bson(&game).map_err(|error| error.to_string())? {
+                Bson::Document(mut document) => {
+                    document.insert("_id", id);
+                    match self.collection.insert_one(document, None) {
+                        Ok(_) => Ok(game.gameNumber),
+                        // another request stored the same game first
+                        Err(error) if is_duplicate_key(&error) => match &game.ClientId {
+                            Some(client_id) => self
+                                .by_client_id(client_id)?
+                                .map(|stored| stored.gameNumber)
+                                .ok_or_else(|| error.to_string()),
+                            None => Err(error.to_string()),
+                        },
+                        Err(error) => Err(error.to_string()),
+                    }
+                }
+                _ => Err(String::from("game didn't convert to a document")),
+            }
+        }
+
+        fn delete(&self, id: &str) -> Result<bool, String> {
+            let id = match ObjectId::with_string(id) {
+                Ok(id) => id,
+                Err(_) => return Ok(false),
+            };
+            let result = self
+                .collection
+                .delete_one(doc! { "_id": id }, None)
+                .map_err(|error| error.to_string())?;
+            Ok(result.deleted_count > 0)
+        }
+
+        fn get(&self, id: &str) -> Result<Option<Game>, String> {
+            let id = match ObjectId::with_string(id) {
+                Ok(id) => id,
+                Err(_) => return Ok(None),
+            };
+            let document = self
+                .collection
+                .find_one(doc! { "_id": id }, None)
+                .map_err(|error| error.to_string())?;
+            Ok(document.and_then(MongoStore::game))
+        }
+
+        fn by_client_id(&self, client_id: &str) -> Result<Option<Game>, String> {
+            let document = self
+                .collection
+                .find_one(doc! { "ClientId": client_id }, None)
+                .map_err(|error| error.to_string())?;
+            Ok(document.and_then(MongoStore::game))
+        }
+
+        // filters, sorts and pages in the database rather than loading every game
+        fn find(&self, query: &Query) -> Result<Page, String> {
+            let filter = query.filter();
+            let total = self
+                .collection
+                .count_documents(filter.clone(), None)
+                .map_err(|error| error.to_string())?;
+
+            // _id breaks ties so pages don't overlap, in saving order that follows the sort
+            // direction like the in-memory sort
+            let direction = if query.descending { -1 } else { 1 };
+            let mut options = FindOptions::default();
+            options.sort = Some(doc! { query.sort.key(): direction, "_id": direction });
+            options.skip = Some(query.offset as i64);
+            options.limit = Some(query.limit as i64);
+
+            let items = self
+                .collection
+                .find(filter, options)
+                .map_err(|error| error.to_string())?
+                .filter_map(Result::ok)
+                .filter_map(MongoStore::game)
+                .collect();
+            Ok(Page { total: total as usize, offset: query.offset, limit: query.limit, items })
+        }
+
+        fn summary(&self) -> Result<Vec<GameTypeSummary>, String> {
+            self.aggregate(stats::summary_pipeline())
+        }
+
+        fn computer_records(&self) -> Result<Vec<ComputerRecord>, String> {
+            let mut records = self.aggregate(stats::computer_pipeline())?;
+            stats::sort_records(&mut records);
+            Ok(records)
+        }
+
+        fn player_wins(&self, game_type: Option<&str>) -> Result<Vec<PlayerWins>, String> {
+            self.aggregate(stats::players_pipeline(game_type))
+        }
+
+        fn player_games(&self, name: &str) -> Result<Vec<Game>, String> {
+            let filter = doc! { "$or": [{ "Player1Name": name }, { "Player2Name": name }] };
+            let cursor = self.collection.find(filter, None).map_err(|error| error.to_string())?;
+            Ok(cursor.filter_map(Result::ok).filter_map(MongoStore::game).collect())
+        }
+    }
+
+    // games kept as a JSON array in a single file, needs no external services
+    pub struct FileStore {
+        path: PathBuf,
+        games: Mutex<Vec<Game>>,
+    }
+
+    // the same kind of id the MongoDB store gives its games
+    fn new_id() -> Result<String, String> {
+        ObjectId::new().map(|id| id.to_hex()).map_err(|error| error.to_string())
+    }
+
+    // reads the JSON array in path, a missing file is an empty store
+    fn load<T: DeserializeOwned>(path: &PathBuf) -> Result<Vec<T>, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|error| error.to_string()),
+            Err(_) if !path.exists() => Ok(Vec::new()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    // rewrites the whole file so it's always a valid array
+    fn save<T: Serialize>(path: &PathBuf, items: &[T]) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(items).map_err(|error| error.to_string())?;
+        fs::write(path, contents).map_err(|error| error.to_string())
+    }
+
+    impl FileStore {
+        // games saved before games had ids are given one, so they can be linked to
+        pub fn open(path: PathBuf) -> Result<FileStore, String> {
+            let mut games: Vec<Game> = load(&path)?;
+            let mut assigned = false;
+            for game in games.iter_mut().filter(|game| game.gameNumber.is_empty()) {
+                game.gameNumber = new_id()?;
+                assigned = true;
+            }
+            if assigned {
+                save(&path, &games)?;
+            }
+            Ok(FileStore { path, games: Mutex::new(games) })
+        }
+    }
+
+    impl GameStore for FileStore {
+        fn all(&self) -> Result<Vec<Game>, String> {
+            Ok(self.games.lock().unwrap().clone())
+        }
+
+        fn insert(&self, game: &Game) -> Result<String, String> {
+            let id = new_id()?;
+            let mut games = self.games.lock().unwrap();
+            if let Some(client_id) = &game.ClientId {
+                if let Some(stored) = games.iter().find(|stored| stored.ClientId.as_ref() == Some(client_id)) {
+                    return Ok(stored.gameNumber.clone());
+                }
+            }
+            games.push(Game { gameNumber: id.clone(), ..game.clone() });
+            if let Err(error) = save(&self.path, &games) {
+                games.pop();
+                return Err(error);
+            }
+            Ok(id)
+        }
+
+        fn delete(&self, id: &str) -> Result<bool, String> {
+            let mut games = self.games.lock().unwrap();
+            let index = match games.iter().position(|game| game.gameNumber == id) {
+                Some(index) => index,
+                None => return Ok(false),
+            };
+            let game = games.remove(index);
+            if let Err(error) = save(&self.path, &games) {
+                games.insert(index, game);
+                return Err(error);
+            }
+            Ok(true)
+        }
+    }
+
+    // registered players, looked up by User::key so names differing only in case are one account
+    pub trait UserStore: Send + Sync {
+        fn user(&self, key: &str) -> Result<Option<User>, String>;
+
+        // adds user, Ok(false) when the name is already taken
+        fn insert_user(&self, user: &User) -> Result<bool, String>;
+    }
+
+    pub struct MongoUsers {
+        collection: Collection,
+    }
+
+    impl MongoUsers {
+        pub fn connect(uri: &str, database: &str, collection: &str) -> Result<MongoUsers, mongodb::error::Error> {
+            let collection = mongodb::Client::with_uri_str(uri)?
+                .database(database)
+                .collection(collection);
+            Ok(MongoUsers { collection })
+        }
+    }
+
+    impl UserStore for MongoUsers {
+        fn user(&self, key: &str) -> Result<Option<User>, String> {
+            let document = self
+                .collection
+                .find_one(doc! { "key": key }, None)
+                .map_err(|error| error.to_string())?;
+            match document {
+                Some(document) => bson::from_bson(Bson::Document(document))
+                    .map(Some)
+                    .map_err(|error| error.to_string()),
+                None => Ok(None),
+            }
+        }
+
+        fn insert_user(&self, user: &User) -> Result<bool, String> {
+            if self.user(&user.key)?.is_some() {
+                return Ok(false);
+            }
+            match bson::to_bson(user).map_err(|error| error.to_string())? {
+                Bson::Document(document) => {
+                    self.collection
+                        .insert_one(document, None)
+                        .map_err(|error| error.to_string())?;
+                    Ok(true)
+                }
+                _ => Err(String::from("user didn't serialize to a document")),
+            }
+        }
+    }
+
+    pub struct FileUsers {
+        path: PathBuf,
+        users: Mutex<Vec<User>>,
+    }
+
+    impl FileUsers {
+        pub fn open(path: PathBuf) -> Result<FileUsers, String> {
+            let users = load(&path)?;
+            Ok(FileUsers { path, users: Mutex::new(users) })
+        }
+    }
+
+    impl UserStore for FileUsers {
+        fn user(&self, key: &str) -> Result<Option<User>, String> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|user| user.key == key).cloned())
+        }
+
+        fn insert_user(&self, user: &User) -> Result<bool, String> {
+            let mut users = self.users.lock().unwrap();
+            if users.iter().any(|existing| existing.key == user.key) {
+                return Ok(false);
+            }
+            users.push(user.clone());
+            if let Err(error) = save(&self.path, &users) {
+                users.pop();
+                return Err(error);
+            }
+            Ok(true)
+        }
+    }
+
+    pub fn open(settings: &StoreSettings) -> Result<Box<dyn GameStore>, String> {
+        match settings {
+            StoreSettings::Mongo { uri, database, collection, .. } => MongoStore::connect(uri, database, collection)
+                .map(|store| Box::new(store) as Box<dyn GameStore>)
+                .map_err(|error| error.to_string()),
+            StoreSettings::File { games, .. } => FileStore::open(games.clone())
+                .map(|store| Box::new(store) as Box<dyn GameStore>),
+        }
+    }
+
+    pub fn open_users(settings: &StoreSettings) -> Result<Box<dyn UserStore>, String> {
+        match settings {
+            StoreSettings::Mongo { uri, database, users_collection, .. } => MongoUsers::connect(uri, database, users_collection)
+                .map(|store| Box::new(store) as Box<dyn UserStore>)
+                .map_err(|error| error.to_string()),
+            StoreSettings::File { users, .. } => FileUsers::open(users.clone())
+                .map(|store| Box::new(store) as Box<dyn UserStore>),
+        }
+    }
+}
+
+fn rocket() -> rocket::Rocket {
+    // create the web server object
+    rocket::ignite()
+        .attach(AdHoc::on_attach("Settings", config::attach))
+        .manage(rooms::Rooms::default())
+        .mount("/", routes![
+            games::get, 
+            games::post,
+            games::get_one,
+            games::delete,
+            stats::summary,
+            stats::computer,
+            stats::players,
+            ratings::get,
+            players::get,
+            rooms::create,
+            rooms::join,
+            rooms::get,
+            rooms::play,
+            accounts::register,
+            accounts::login,
+            accounts::current,
+            accounts::logout,
+        ])
+        .register(catchers![
+            games::bad_request,
+            games::unauthorized,
+            games::forbidden,
+            games::not_found,
+            games::conflict,
+            games::unprocessable,
+            games::internal_error,
+        ])
+}
+
+fn main() {
+    // launch server or report error
+    let error = rocket().launch();
+    eprintln!("Failed to launch server: {}", error);
 }
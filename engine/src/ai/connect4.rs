@@ -0,0 +1,162 @@
+use crate::ai::tt::{Bound, Stats, TranspositionTable};
+use crate::bitboard::{self, Position};
+use crate::board::{COLUMNS, ROWS};
+
+// score of a win on the next move, quicker wins score higher
+pub const WIN: i32 = 1_000_000;
+
+const INFINITY: i32 = 2 * WIN;
+
+// entries in the transposition table
+const TABLE_SIZE: usize = 1 << 16;
+
+// columns from the centre outwards, central discs take part in the most lines
+pub const ORDER: [usize; COLUMNS] = [3, 2, 4, 1, 5, 0, 6];
+
+// depth limited alpha-beta search over bitboards
+pub struct Search {
+    windows: Vec<u64>,
+    table: TranspositionTable,
+    nodes: u64,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Search {
+            windows: bitboard::windows(),
+            table: TranspositionTable::new(TABLE_SIZE),
+            nodes: 0,
+        }
+    }
+
+    // positions visited since the search was created
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub fn table_stats(&self) -> Stats {
+        self.table.stats()
+    }
+
+    // the score and every best column for the side to move, looking depth plies ahead
+    pub fn best_moves(&mut self, position: &Position, depth: u32) -> (i32, Vec<usize>) {
+        let mut position = *position;
+        let mut best = -INFINITY;
+        let mut moves = Vec::new();
+
+        for &column in ORDER.iter() {
+            if !position.can_play(column) {
+                continue;
+            }
+            let score = if position.is_winning_move(column) {
+                win_score(&position)
+            } else {
+                position.play(column);
+                // a window just below best still tells ties apart from worse moves
+                let score = -self.negamax(&mut position, depth.max(1) - 1, -INFINITY, 1 - best);
+                position.undo(column);
+                score
+            };
+
+            if score > best {
+                best = score;
+                moves = vec![column];
+            } else if score == best {
+                moves.push(column);
+            }
+        }
+
+        (best, moves)
+    }
+
+    fn negamax(
+        &mut self,
+        position: &mut Position,
+        depth: u32,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+
+        if ORDER
+            .iter()
+            .any(|&column| position.can_play(column) && position.is_winning_move(column))
+        {
+            return win_score(position);
+        }
+        if position.moves() == ROWS * COLUMNS {
+            return 0;
+        }
+        if depth == 0 {
+            return self.evaluate(position);
+        }
+
+        let key = position.hash();
+        if let Some(entry) = self.table.probe(key) {
+            if entry.depth as u32 >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.value,
+                    Bound::Lower => alpha = alpha.max(entry.value),
+                    Bound::Upper => beta = beta.min(entry.value),
+                }
+                if alpha >= beta {
+                    return entry.value;
+                }
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        for &column in ORDER.iter() {
+            if !position.can_play(column) {
+                continue;
+            }
+            position.play(column);
+            let score = -self.negamax(position, depth - 1, -beta, -alpha);
+            position.undo(column);
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(key, depth as u8, best, bound);
+        best
+    }
+
+    // open lines cubed, from the point of view of the side to move
+    fn evaluate(&self, position: &Position) -> i32 {
+        let own = position.discs(position.to_move());
+        let other = position.discs(position.to_move().other());
+
+        self.windows.iter().fold(0, |score, &window| {
+            let mine = (own & window).count_ones() as i32;
+            let theirs = (other & window).count_ones() as i32;
+            match (mine, theirs) {
+                (mine, 0) => score + mine * mine * mine,
+                (0, theirs) => score - theirs * theirs * theirs,
+                _ => score,
+            }
+        })
+    }
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Search::new()
+    }
+}
+
+// value of winning with the next disc dropped
+fn win_score(position: &Position) -> i32 {
+    WIN - position.moves() as i32 - 1
+}
@@ -0,0 +1,201 @@
+use crate::ai::tt::{Bound, Stats, TranspositionTable};
+use crate::board::Board;
+use crate::game::{GameState, Move, Outcome, Player};
+
+// score of a win on the next move, quicker wins score higher
+pub const WIN: i32 = 1_000_000;
+
+const INFINITY: i32 = 2 * WIN;
+
+// entries in the transposition table
+const TABLE_SIZE: usize = 1 << 16;
+
+// depth limited alpha-beta search over any board size and line length, and over
+// Pop-Out and Power-Up, slower than the bitboard search that plays the standard board
+pub struct Search {
+    lines: Vec<Vec<(usize, usize)>>,
+    table: TranspositionTable,
+    nodes: u64,
+}
+
+impl Search {
+    pub fn new(state: &GameState) -> Self {
+        Search {
+            lines: state.board().lines(),
+            table: TranspositionTable::new(TABLE_SIZE),
+            nodes: 0,
+        }
+    }
+
+    // positions visited since the search was created
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub fn table_stats(&self) -> Stats {
+        self.table.stats()
+    }
+
+    // the score and every best move for the side to move, looking depth plies ahead
+    pub fn best_moves(&mut self, state: &GameState, depth: u32) -> (i32, Vec<Move>) {
+        let mut state = state.clone();
+        let mut best = -INFINITY;
+        let mut moves = Vec::new();
+
+        for mv in ordered_moves(&state) {
+            let mover = state.to_move();
+            state.apply_move(mv).unwrap();
+            // a window just below best still tells ties apart from worse moves
+            let score = if state.to_move() == mover {
+                self.negamax(&mut state, depth.max(1) - 1, best - 1, INFINITY)
+            } else {
+                -self.negamax(&mut state, depth.max(1) - 1, -INFINITY, 1 - best)
+            };
+            state.undo_move();
+
+            if score > best {
+                best = score;
+                moves = vec![mv];
+            } else if score == best {
+                moves.push(mv);
+            }
+        }
+
+        (best, moves)
+    }
+
+    // the best moves of the deepest search that fits in node_limit, deepening two
+    // plies at a time
+    pub fn deepest_moves(&mut self, state: &GameState, node_limit: u64) -> Vec<Move> {
+        let empty = state.board().rows() * state.board().columns() - state.board().count();
+        let mut moves = Vec::new();
+        let mut depth = 2;
+        let mut last = self.nodes;
+        while depth as usize <= empty {
+            let (_, best) = self.best_moves(state, depth);
+            moves = best;
+
+            // each extra pair of plies costs several times the last iteration
+            let cost = self.nodes - last;
+            last = self.nodes;
+            if last + 8 * cost > node_limit {
+                break;
+            }
+            depth += 2;
+        }
+        moves
+    }
+
+    fn negamax(&mut self, state: &mut GameState, depth: u32, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+
+        match state.outcome() {
+            // a Pop-Out pop can complete a line for the side to move
+            Some(Outcome::Win(winner)) if winner == state.to_move() => {
+                return WIN - state.move_count() as i32
+            }
+            Some(Outcome::Win(_)) => return -(WIN - state.move_count() as i32),
+            Some(Outcome::Draw) => return 0,
+            None => {}
+        }
+        if depth == 0 {
+            return self.evaluate(state.board(), state.to_move());
+        }
+
+        let key = state.hash();
+        if let Some(entry) = self.table.probe(key) {
+            if entry.depth as u32 >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.value,
+                    Bound::Lower => alpha = alpha.max(entry.value),
+                    Bound::Upper => beta = beta.min(entry.value),
+                }
+                if alpha >= beta {
+                    return entry.value;
+                }
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        for mv in ordered_moves(state) {
+            let mover = state.to_move();
+            state.apply_move(mv).unwrap();
+            // after a Power::Double the same player moves again, so the score stays theirs
+            let score = if state.to_move() == mover {
+                self.negamax(state, depth - 1, alpha, beta)
+            } else {
+                -self.negamax(state, depth - 1, -beta, -alpha)
+            };
+            state.undo_move();
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(key, depth as u8, best, bound);
+        best
+    }
+
+    // open lines cubed, from the point of view of player
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        self.lines.iter().fold(0, |score, line| {
+            let (mut mine, mut theirs) = (0, 0);
+            for &(row, column) in line {
+                match board.get(row, column).map(|cell| cell.owner()) {
+                    Some(Some(owner)) if owner == player => mine += 1,
+                    Some(Some(_)) => theirs += 1,
+                    // a wall closes the line for both players
+                    Some(None) => return score,
+                    None => {}
+                }
+            }
+            match (mine, theirs) {
+                (mine, 0) => score + mine * mine * mine,
+                (0, theirs) => score - theirs * theirs * theirs,
+                _ => score,
+            }
+        })
+    }
+}
+
+// legal moves from the centre column outwards, central discs take part in the most lines
+fn ordered_moves(state: &GameState) -> Vec<Move> {
+    let columns = state.board().columns() as isize;
+    let mut moves = state.legal_moves();
+    moves.sort_by_key(|mv| {
+        let offset = 2 * mv.column as isize - (columns - 1);
+        (offset.abs(), offset)
+    });
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameKind, Power};
+
+    #[test]
+    fn double_then_drop_wins() {
+        // Player::One has columns 2 and 3 of the bottom row, only a Double followed by a
+        // drop completes four within two plies
+        let mut state = GameState::new(GameKind::PowerUp);
+        for &column in [2, 2, 3, 3].iter() {
+            state.apply_move(Move::drop(column)).unwrap();
+        }
+        let (score, moves) = Search::new(&state).best_moves(&state, 2);
+        assert_eq!(score, WIN - 6);
+        assert!(!moves.is_empty());
+        assert!(moves.iter().all(|mv| mv.power == Some(Power::Double)));
+    }
+}
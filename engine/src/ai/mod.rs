@@ -0,0 +1,8 @@
+//! Computer players. Searches report every equally good move so callers can pick
+//! between them however they like.
+
+pub mod connect4;
+pub mod connect_n;
+pub mod solver;
+pub mod toot_otto;
+pub mod tt;
@@ -0,0 +1,333 @@
+use std::cmp::Reverse;
+
+use crate::ai::connect4::{Search, ORDER};
+use crate::ai::tt::{Bound, Stats, TranspositionTable};
+use crate::bitboard::{self, Position};
+use crate::board::{COLUMNS, ROWS};
+
+const CELLS: i32 = (ROWS * COLUMNS) as i32;
+
+// scores count how early the game is won: a win leaves
+// (CELLS / 2 + 1 - discs played by the winner) as the score
+
+// entries in the transposition table
+const TABLE_SIZE: usize = 1 << 18;
+
+// positions searched per call before the solver gives up
+pub const DEFAULT_NODE_LIMIT: u64 = 2_000_000;
+
+// theoretical result of a position for the side to move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prediction {
+    // wins with perfect play, needing this many more discs
+    Win(usize),
+    // loses against perfect play, the opponent needs this many more discs
+    Loss(usize),
+    Draw,
+}
+
+impl Prediction {
+    pub fn from_score(position: &Position, score: i32) -> Self {
+        let moves = position.moves() as i32;
+        if score > 0 {
+            let own = moves / 2;
+            Prediction::Win((CELLS / 2 + 1 - score - own) as usize)
+        } else if score < 0 {
+            let other = (moves + 1) / 2;
+            Prediction::Loss((CELLS / 2 + 1 + score - other) as usize)
+        } else {
+            Prediction::Draw
+        }
+    }
+}
+
+// exact Connect4 solver: negamax with alpha-beta pruning, narrowed with null windows
+pub struct Solver {
+    table: TranspositionTable,
+    nodes: u64,
+    node_limit: u64,
+    aborted: bool,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver {
+            table: TranspositionTable::new(TABLE_SIZE),
+            nodes: 0,
+            node_limit: DEFAULT_NODE_LIMIT,
+            aborted: false,
+        }
+    }
+
+    // caps the positions searched by every solve
+    pub fn with_node_limit(mut self, node_limit: u64) -> Self {
+        self.node_limit = node_limit;
+        self
+    }
+
+    // positions visited by the last call
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    // transposition table counters over the solver's lifetime
+    pub fn table_stats(&self) -> Stats {
+        self.table.stats()
+    }
+
+    // exact score for the side to move, None if it couldn't be proven within the node limit
+    pub fn solve(&mut self, position: &Position) -> Option<i32> {
+        self.nodes = 0;
+        self.aborted = false;
+        let score = self.search(position);
+        if self.aborted {
+            None
+        } else {
+            Some(score)
+        }
+    }
+
+    // exact scores of every playable column, None if any couldn't be proven
+    pub fn scores(&mut self, position: &Position) -> Option<Vec<(usize, i32)>> {
+        self.nodes = 0;
+        self.aborted = false;
+
+        let mut scores = Vec::new();
+        for &column in ORDER.iter() {
+            if !position.can_play(column) {
+                continue;
+            }
+            let score = if position.is_winning_move(column) {
+                (CELLS + 1 - position.moves() as i32) / 2
+            } else {
+                let mut next = *position;
+                next.play(column);
+                let score = self.search(&next);
+                if self.aborted {
+                    return None;
+                }
+                -score
+            };
+            scores.push((column, score));
+        }
+        Some(scores)
+    }
+
+    // perfect moves and the predicted result when the position can be solved in
+    // time, otherwise the deepest heuristic search that fits in the node limit
+    pub fn best_moves(&mut self, position: &Position) -> (Option<Prediction>, Vec<usize>) {
+        if let Some(scores) = self.scores(position) {
+            let best = match scores.iter().map(|&(_, score)| score).max() {
+                Some(best) => best,
+                None => return (Some(Prediction::Draw), Vec::new()),
+            };
+            let moves = scores
+                .iter()
+                .filter(|&&(_, score)| score == best)
+                .map(|&(column, _)| column)
+                .collect();
+            return (Some(Prediction::from_score(position, best)), moves);
+        }
+
+        let mut search = Search::new();
+        let mut moves = Vec::new();
+        let mut depth = 2;
+        let mut last = 0;
+        while depth as usize <= ROWS * COLUMNS - position.moves() {
+            let (_, best) = search.best_moves(position, depth);
+            moves = best;
+
+            // each extra pair of plies costs several times the last iteration
+            let cost = search.nodes() - last;
+            last = search.nodes();
+            if last + 8 * cost > self.node_limit {
+                break;
+            }
+            depth += 2;
+        }
+        self.nodes += search.nodes();
+        (None, moves)
+    }
+
+    // iterative deepening over the score window, wins close by are found first
+    fn search(&mut self, position: &Position) -> i32 {
+        if position.can_win_next() {
+            return (CELLS + 1 - position.moves() as i32) / 2;
+        }
+
+        let mut min = -(CELLS - position.moves() as i32) / 2;
+        let mut max = (CELLS + 1 - position.moves() as i32) / 2;
+        while min < max && !self.aborted {
+            let mut med = min + (max - min) / 2;
+            if med <= 0 && min / 2 < med {
+                med = min / 2;
+            } else if med >= 0 && max / 2 > med {
+                med = max / 2;
+            }
+
+            let score = self.negamax(position, med, med + 1);
+            if score <= med {
+                max = score;
+            } else {
+                min = score;
+            }
+        }
+        min
+    }
+
+    // the side to move must not be able to win on this move
+    fn negamax(&mut self, position: &Position, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.nodes > self.node_limit {
+            self.aborted = true;
+            return alpha;
+        }
+
+        let moves = position.moves() as i32;
+        let possible = position.non_losing_moves();
+        if possible == 0 {
+            return -(CELLS - moves) / 2;
+        }
+        if moves >= CELLS - 2 {
+            return 0;
+        }
+
+        // the opponent can't win on their next move
+        let min = -(CELLS - 2 - moves) / 2;
+        if alpha < min {
+            alpha = min;
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+
+        // we can't win on this move
+        let max = match self.table.probe(position.hash()) {
+            Some(entry) if entry.bound == Bound::Upper => entry.value,
+            _ => (CELLS - 1 - moves) / 2,
+        };
+        if beta > max {
+            beta = max;
+            if alpha >= beta {
+                return beta;
+            }
+        }
+
+        // most new threats first, centre first among equals
+        let mut columns: Vec<(u32, usize)> = ORDER
+            .iter()
+            .filter(|&&column| possible & bitboard::column_mask(column) != 0)
+            .map(|&column| (position.threats_after(column), column))
+            .collect();
+        columns.sort_by_key(|&(threats, _)| Reverse(threats));
+
+        for (_, column) in columns {
+            let mut next = *position;
+            next.play(column);
+            let score = -self.negamax(&next, -beta, -alpha);
+            if self.aborted {
+                return alpha;
+            }
+            if score >= beta {
+                return score;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+        }
+
+        // entries are searched to the end of the game
+        let depth = (CELLS - moves) as u8;
+        self.table
+            .store(position.hash(), depth, alpha, Bound::Upper);
+        alpha
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(columns: &[usize]) -> Position {
+        let mut position = Position::new();
+        for &column in columns {
+            position.play(column);
+        }
+        position
+    }
+
+    // plain negamax over the whole tree, only usable close to the end of the game
+    fn exhaustive(position: &Position) -> i32 {
+        let moves = position.moves() as i32;
+        if moves == CELLS {
+            return 0;
+        }
+        let mut best = -CELLS;
+        for column in 0..COLUMNS {
+            if !position.can_play(column) {
+                continue;
+            }
+            if position.is_winning_move(column) {
+                return (CELLS + 1 - moves) / 2;
+            }
+            let mut next = *position;
+            next.play(column);
+            best = best.max(-exhaustive(&next));
+        }
+        best
+    }
+
+    #[test]
+    fn immediate_win_scores_by_discs_left() {
+        // Player::One has three in column 0 and wins with their fourth disc
+        let position = position(&[0, 1, 0, 1, 0, 1]);
+        assert_eq!(Solver::new().solve(&position), Some(CELLS / 2 + 1 - 4));
+    }
+
+    #[test]
+    fn open_three_loses() {
+        // Player::One has columns 1 to 3 of the bottom row with both ends open, so
+        // Player::Two can only block one of them
+        let position = position(&[1, 1, 2, 2, 3]);
+        let mut solver = Solver::new();
+        assert_eq!(solver.solve(&position), Some(-(CELLS / 2 + 1 - 4)));
+        let (prediction, _) = solver.best_moves(&position);
+        assert_eq!(prediction, Some(Prediction::Loss(1)));
+    }
+
+    #[test]
+    fn endgames_match_an_exhaustive_search() {
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut checked = 0;
+        while checked < 20 {
+            // a random game stopped with ten cells left, or before anyone can win, which
+            // is skipped
+            let mut position = Position::new();
+            while position.moves() < (CELLS - 10) as usize && !position.can_win_next() {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let column = (seed % COLUMNS as u64) as usize;
+                if position.can_play(column) {
+                    position.play(column);
+                }
+            }
+            if position.can_win_next() {
+                continue;
+            }
+
+            let expected = exhaustive(&position);
+            let mut solver = Solver::new();
+            assert_eq!(solver.solve(&position), Some(expected));
+            let scores = solver.scores(&position).unwrap();
+            assert_eq!(scores.iter().map(|&(_, score)| score).max(), Some(expected));
+            checked += 1;
+        }
+    }
+}
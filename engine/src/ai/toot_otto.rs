@@ -0,0 +1,223 @@
+use crate::ai::connect4::ORDER;
+use crate::ai::tt::{Bound, Stats, TranspositionTable};
+use crate::board::Board;
+use crate::game::{GameState, Move, Outcome, Player};
+use crate::toot_otto::{OTTO, TOOT};
+
+// score of a win on the next move, quicker wins score higher
+pub const WIN: i32 = 1_000_000;
+
+const INFINITY: i32 = 2 * WIN;
+
+// entries in the transposition table
+const TABLE_SIZE: usize = 1 << 16;
+
+// depth limited alpha-beta search over TOOT-OTTO positions, scores are from the
+// point of view of the side to move so it plays for whichever word is its own
+pub struct Search {
+    lines: Vec<Vec<(usize, usize)>>,
+    table: TranspositionTable,
+    nodes: u64,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Search {
+            lines: Board::new().lines(),
+            table: TranspositionTable::new(TABLE_SIZE),
+            nodes: 0,
+        }
+    }
+
+    // positions visited since the search was created
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub fn table_stats(&self) -> Stats {
+        self.table.stats()
+    }
+
+    // the score and every best move for the side to move, looking depth plies ahead
+    pub fn best_moves(&mut self, state: &GameState, depth: u32) -> (i32, Vec<Move>) {
+        let mut state = state.clone();
+        let mut best = -INFINITY;
+        let mut moves = Vec::new();
+
+        for mv in ordered_moves(&state) {
+            state.apply_move(mv).unwrap();
+            // a window just below best still tells ties apart from worse moves
+            let score = -self.negamax(&mut state, depth.max(1) - 1, -INFINITY, 1 - best);
+            state.undo_move();
+
+            if score > best {
+                best = score;
+                moves = vec![mv];
+            } else if score == best {
+                moves.push(mv);
+            }
+        }
+
+        (best, moves)
+    }
+
+    fn negamax(&mut self, state: &mut GameState, depth: u32, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+
+        let player = state.to_move();
+        match state.outcome() {
+            // either player's move can spell either word, so the side to move may
+            // have just been handed the win
+            Some(Outcome::Win(winner)) if winner == player => {
+                return WIN - state.move_count() as i32
+            }
+            Some(Outcome::Win(_)) => return -(WIN - state.move_count() as i32),
+            Some(Outcome::Draw) => return 0,
+            None => {}
+        }
+        if depth == 0 {
+            return self.evaluate(state.board(), player);
+        }
+
+        let key = state.hash();
+        if let Some(entry) = self.table.probe(key) {
+            if entry.depth as u32 >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.value,
+                    Bound::Lower => alpha = alpha.max(entry.value),
+                    Bound::Upper => beta = beta.min(entry.value),
+                }
+                if alpha >= beta {
+                    return entry.value;
+                }
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        for mv in ordered_moves(state) {
+            state.apply_move(mv).unwrap();
+            let score = -self.negamax(state, depth - 1, -beta, -alpha);
+            state.undo_move();
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(key, depth as u8, best, bound);
+        best
+    }
+
+    // every line that can still spell a word scores the cube of the letters already
+    // in place, for player when it is their word and against them otherwise
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        let mut score = 0;
+        for line in self.lines.iter() {
+            for &(word, owner) in &[(TOOT, Player::One), (OTTO, Player::Two)] {
+                let mut placed = 0;
+                let mut open = true;
+                for (k, &(row, column)) in line.iter().enumerate() {
+                    match board.get(row, column).and_then(|cell| cell.letter) {
+                        Some(letter) if letter == word[k] => placed += 1,
+                        Some(_) => {
+                            open = false;
+                            break;
+                        }
+                        None => {}
+                    }
+                }
+
+                if open {
+                    let value = placed * placed * placed;
+                    score += if owner == player { value } else { -value };
+                }
+            }
+        }
+        score
+    }
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Search::new()
+    }
+}
+
+// legal moves from the centre column outwards
+fn ordered_moves(state: &GameState) -> Vec<Move> {
+    let mut moves = state.legal_moves();
+    moves.sort_by_key(|mv| ORDER.iter().position(|&column| column == mv.column));
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameKind, Letter};
+
+    fn play(moves: &[(usize, Letter)]) -> GameState {
+        let mut state = GameState::new(GameKind::TootOtto);
+        for &(column, letter) in moves {
+            state.apply_move(Move::letter(column, letter)).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn spells_its_own_word() {
+        // Player::One has T O O along the bottom row and finishes TOOT
+        let state = play(&[
+            (0, Letter::T),
+            (1, Letter::O),
+            (2, Letter::O),
+            (6, Letter::O),
+        ]);
+        let (score, moves) = Search::new().best_moves(&state, 2);
+        assert_eq!(score, WIN - 5);
+        assert_eq!(moves, vec![Move::letter(3, Letter::T)]);
+    }
+
+    #[test]
+    fn blocks_the_other_word() {
+        // O T T along the bottom row, an O from either player would spell OTTO for
+        // Player::Two, so Player::One has to close it with a T
+        let state = play(&[
+            (0, Letter::O),
+            (1, Letter::T),
+            (2, Letter::T),
+            (6, Letter::T),
+        ]);
+        let (score, moves) = Search::new().best_moves(&state, 2);
+        assert!(score > -WIN / 2);
+        assert_eq!(moves, vec![Move::letter(3, Letter::T)]);
+    }
+
+    #[test]
+    fn evaluation_is_zero_sum() {
+        let search = Search::new();
+        let state = play(&[
+            (3, Letter::T),
+            (3, Letter::O),
+            (2, Letter::O),
+            (4, Letter::T),
+            (1, Letter::O),
+        ]);
+        let board = state.board();
+        assert_eq!(search.evaluate(&Board::new(), Player::One), 0);
+        assert_eq!(
+            search.evaluate(board, Player::One),
+            -search.evaluate(board, Player::Two)
+        );
+        assert_ne!(search.evaluate(board, Player::One), 0);
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+// how a stored value relates to the true value of the position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    // the true value is at least this
+    Lower,
+    // the true value is at most this
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: u8,
+    pub value: i32,
+    pub bound: Bound,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    // stores that evicted a different position
+    pub replacements: u64,
+    // stores dropped in favour of a deeper entry
+    pub rejections: u64,
+}
+
+impl Stats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.probes as f64
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} hits ({:.1}%), {} stores, {} replaced, {} rejected",
+            self.hits,
+            self.probes,
+            100.0 * self.hit_rate(),
+            self.stores,
+            self.replacements,
+            self.rejections
+        )
+    }
+}
+
+// fixed-size table of search results keyed by Zobrist hash, one entry per slot,
+// keeping whichever of two colliding entries was searched deeper
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    stats: Stats,
+}
+
+impl TranspositionTable {
+    // capacity is rounded up to a power of two
+    pub fn new(capacity: usize) -> Self {
+        TranspositionTable {
+            entries: vec![None; capacity.next_power_of_two()],
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn probe(&mut self, key: u64) -> Option<Entry> {
+        self.stats.probes += 1;
+        match self.entries[self.slot(key)] {
+            Some(entry) if entry.key == key => {
+                self.stats.hits += 1;
+                Some(entry)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn store(&mut self, key: u64, depth: u8, value: i32, bound: Bound) {
+        let slot = self.slot(key);
+        if let Some(old) = self.entries[slot] {
+            if old.key != key {
+                if old.depth > depth {
+                    self.stats.rejections += 1;
+                    return;
+                }
+                self.stats.replacements += 1;
+            }
+        }
+
+        self.stats.stores += 1;
+        self.entries[slot] = Some(Entry {
+            key,
+            depth,
+            value,
+            bound,
+        });
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probes_count_hits_and_misses() {
+        let mut table = TranspositionTable::new(16);
+        assert_eq!(table.probe(5), None);
+        table.store(5, 3, 42, Bound::Exact);
+        let entry = table.probe(5).unwrap();
+        assert_eq!(
+            (entry.depth, entry.value, entry.bound),
+            (3, 42, Bound::Exact)
+        );
+        // 21 shares 5's slot in a table of 16
+        assert_eq!(table.probe(21), None);
+
+        let stats = table.stats();
+        assert_eq!((stats.probes, stats.hits, stats.stores), (3, 1, 1));
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn deeper_entries_win_collisions() {
+        // 12 entries round up to 16 slots, so 1 and 17 collide
+        let mut table = TranspositionTable::new(12);
+        table.store(1, 4, 10, Bound::Lower);
+        table.store(17, 2, 20, Bound::Upper);
+        assert_eq!(table.probe(1).map(|entry| entry.value), Some(10));
+        assert_eq!(table.probe(17), None);
+
+        table.store(17, 4, 30, Bound::Exact);
+        assert_eq!(table.probe(1), None);
+        assert_eq!(table.probe(17).map(|entry| entry.value), Some(30));
+
+        // the same position is always updated, even from a shallower search
+        table.store(17, 1, 40, Bound::Exact);
+        assert_eq!(table.probe(17).map(|entry| entry.depth), Some(1));
+
+        let stats = table.stats();
+        assert_eq!(
+            (stats.stores, stats.replacements, stats.rejections),
+            (3, 1, 1)
+        );
+    }
+}
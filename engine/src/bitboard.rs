@@ -0,0 +1,277 @@
+use crate::board::{Cell, COLUMNS, ROWS};
+use crate::game::{Move, Player};
+use crate::zobrist;
+
+// each column takes ROWS + 1 bits, the extra sentinel bit keeps shifted lines from
+// wrapping into the next column; bit 0 of a column is its bottom cell
+const HEIGHT: usize = ROWS + 1;
+
+const BOTTOM: u64 = {
+    let mut mask = 0;
+    let mut column = 0;
+    while column < COLUMNS {
+        mask |= 1 << (column * HEIGHT);
+        column += 1;
+    }
+    mask
+};
+
+const FULL: u64 = BOTTOM * ((1 << ROWS) - 1);
+
+// Connect4 position as one disc mask per player and the height of every column
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    discs: [u64; 2],
+    heights: [u8; COLUMNS],
+    moves: u8,
+    hash: u64,
+}
+
+impl Position {
+    pub fn new() -> Self {
+        Position {
+            discs: [0; 2],
+            heights: [0; COLUMNS],
+            moves: 0,
+            hash: 0,
+        }
+    }
+
+    // replays a Connect4 move list, None if any move is illegal or from another variant
+    pub fn from_moves(moves: &[Move]) -> Option<Self> {
+        let mut position = Position::new();
+        for mv in moves {
+            if mv.letter.is_some() || mv.pop || mv.power.is_some() || !position.can_play(mv.column)
+            {
+                return None;
+            }
+            position.play(mv.column);
+        }
+        Some(position)
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves as usize
+    }
+
+    pub fn to_move(&self) -> Player {
+        match self.moves % 2 {
+            0 => Player::One,
+            _ => Player::Two,
+        }
+    }
+
+    pub fn discs(&self, player: Player) -> u64 {
+        self.discs[index(player)]
+    }
+
+    // every occupied cell
+    pub fn mask(&self) -> u64 {
+        self.discs[0] | self.discs[1]
+    }
+
+    // Zobrist hash, matches GameState::hash for the same Connect4 position
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.mask() == FULL
+    }
+
+    pub fn can_play(&self, column: usize) -> bool {
+        column < COLUMNS && (self.heights[column] as usize) < ROWS
+    }
+
+    // drops a disc for the side to move, column must be playable
+    pub fn play(&mut self, column: usize) {
+        let player = self.to_move();
+        self.discs[index(player)] |= cell(column, self.heights[column] as usize);
+        self.hash ^= self.cell_key(column, player);
+        self.heights[column] += 1;
+        self.moves += 1;
+    }
+
+    // takes back the last disc dropped into column
+    pub fn undo(&mut self, column: usize) {
+        self.moves -= 1;
+        self.heights[column] -= 1;
+        let player = self.to_move();
+        self.discs[index(player)] &= !cell(column, self.heights[column] as usize);
+        self.hash ^= self.cell_key(column, player);
+    }
+
+    // key for a disc of player at the top of column, including the change of turn
+    fn cell_key(&self, column: usize, player: Player) -> u64 {
+        let row = ROWS - 1 - self.heights[column] as usize;
+        let disc = Cell {
+            player,
+            letter: None,
+            power: None,
+        };
+        zobrist::cell(row, column, disc) ^ zobrist::SIDE
+    }
+
+    // would dropping into column complete four in a row for the side to move
+    pub fn is_winning_move(&self, column: usize) -> bool {
+        let discs = self.discs(self.to_move()) | cell(column, self.heights[column] as usize);
+        has_four(discs)
+    }
+
+    pub fn has_won(&self, player: Player) -> bool {
+        has_four(self.discs(player))
+    }
+
+    // the next free cell of every playable column
+    pub fn possible(&self) -> u64 {
+        (self.mask() + BOTTOM) & FULL
+    }
+
+    pub fn can_win_next(&self) -> bool {
+        let own = self.discs(self.to_move());
+        winning_cells(own, self.mask()) & self.possible() != 0
+    }
+
+    // playable cells that don't hand the opponent an immediate win, empty when every
+    // move loses; only meaningful when the side to move can't win straight away
+    pub fn non_losing_moves(&self) -> u64 {
+        let mut possible = self.possible();
+        let threats = winning_cells(self.discs(self.to_move().other()), self.mask());
+        let forced = possible & threats;
+        if forced != 0 {
+            if forced & (forced - 1) != 0 {
+                // two threats can't both be blocked
+                return 0;
+            }
+            possible = forced;
+        }
+        // never play directly below an opponent's winning cell
+        possible & !(threats >> 1)
+    }
+
+    // number of winning cells the side to move would have after dropping into column
+    pub fn threats_after(&self, column: usize) -> u32 {
+        let own = self.discs(self.to_move()) | cell(column, self.heights[column] as usize);
+        winning_cells(own, self.mask()).count_ones()
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::new()
+    }
+}
+
+fn index(player: Player) -> usize {
+    match player {
+        Player::One => 0,
+        Player::Two => 1,
+    }
+}
+
+fn cell(column: usize, row: usize) -> u64 {
+    1 << (column * HEIGHT + row)
+}
+
+// every cell of column
+pub fn column_mask(column: usize) -> u64 {
+    ((1 << ROWS) - 1) << (column * HEIGHT)
+}
+
+// empty cells that would complete four in a row for discs
+fn winning_cells(discs: u64, mask: u64) -> u64 {
+    // vertical lines can only be completed from above
+    let mut cells = (discs << 1) & (discs << 2) & (discs << 3);
+
+    for &shift in &[HEIGHT - 1, HEIGHT, HEIGHT + 1] {
+        let pair = (discs << shift) & (discs << (2 * shift));
+        cells |= pair & (discs << (3 * shift));
+        cells |= pair & (discs >> shift);
+        let pair = (discs >> shift) & (discs >> (2 * shift));
+        cells |= pair & (discs << shift);
+        cells |= pair & (discs >> (3 * shift));
+    }
+
+    cells & (FULL ^ mask)
+}
+
+// four in a row along any direction, each shift steps once along that direction
+pub fn has_four(discs: u64) -> bool {
+    for &shift in &[1, HEIGHT - 1, HEIGHT, HEIGHT + 1] {
+        let pairs = discs & (discs >> shift);
+        if pairs & (pairs >> (2 * shift)) != 0 {
+            return true;
+        }
+    }
+    false
+}
+
+// every run of four cells on the board as a mask, used for evaluation
+pub fn windows() -> Vec<u64> {
+    let mut windows = Vec::new();
+    for column in 0..COLUMNS {
+        for row in 0..ROWS {
+            for &(dc, dr) in &[(1, 0), (0, 1), (1, 1), (1, -1)] {
+                let end_column = column as isize + 3 * dc;
+                let end_row = row as isize + 3 * dr;
+                if end_column >= COLUMNS as isize || end_row < 0 || end_row >= ROWS as isize {
+                    continue;
+                }
+                let window = (0..4).fold(0, |window, k| {
+                    let c = (column as isize + k * dc) as usize;
+                    let r = (row as isize + k * dr) as usize;
+                    window | cell(c, r)
+                });
+                windows.push(window);
+            }
+        }
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::game::{GameKind, GameState};
+
+    // whether player owns every cell of some line on board
+    fn board_has_four(board: &Board, player: Player) -> bool {
+        board.lines().iter().any(|line| {
+            line.iter().all(|&(row, column)| {
+                board.get(row, column).map(|cell| cell.player) == Some(player)
+            })
+        })
+    }
+
+    #[test]
+    fn has_four_agrees_with_board() {
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        for _ in 0..500 {
+            let mut state = GameState::new(GameKind::Connect4);
+            let mut position = Position::new();
+            while !state.is_over() {
+                let moves = state.legal_moves();
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let mv = moves[(seed % moves.len() as u64) as usize];
+                assert_eq!(position.is_winning_move(mv.column), {
+                    let mut next = state.clone();
+                    next.apply_move(mv).unwrap();
+                    next.winner().is_some()
+                });
+                state.apply_move(mv).unwrap();
+                position.play(mv.column);
+
+                for &player in [Player::One, Player::Two].iter() {
+                    assert_eq!(
+                        has_four(position.discs(player)),
+                        board_has_four(state.board(), player)
+                    );
+                }
+                assert_eq!(position.hash(), state.hash());
+            }
+        }
+    }
+}
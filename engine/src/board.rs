@@ -0,0 +1,331 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Letter, Player, Power};
+
+pub const ROWS: usize = 6;
+pub const COLUMNS: usize = 7;
+
+// length of a winning line in both games
+pub const CONNECT: usize = 4;
+
+// limits on the Connect-N boards players can choose
+pub const MIN_SIZE: usize = 4;
+pub const MAX_ROWS: usize = 10;
+pub const MAX_COLUMNS: usize = 10;
+pub const MIN_CONNECT: usize = 3;
+pub const MAX_CONNECT: usize = 6;
+
+// board size and winning line length, TOOT-OTTO is always played on the standard one
+// and 5-in-a-Row on FIVE_IN_A_ROW
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Variant {
+    pub rows: usize,
+    pub columns: usize,
+    pub connect: usize,
+}
+
+impl Variant {
+    pub const STANDARD: Variant = Variant {
+        rows: ROWS,
+        columns: COLUMNS,
+        connect: CONNECT,
+    };
+
+    pub const FIVE_IN_A_ROW: Variant = Variant {
+        rows: 6,
+        columns: 9,
+        connect: 5,
+    };
+
+    pub fn new(rows: usize, columns: usize, connect: usize) -> Result<Variant, String> {
+        let variant = Variant {
+            rows,
+            columns,
+            connect,
+        };
+        variant.check()?;
+        Ok(variant)
+    }
+
+    // variants read from saved games skip new, so they are checked again before use
+    pub fn check(&self) -> Result<(), String> {
+        if self.rows < MIN_SIZE || self.rows > MAX_ROWS {
+            return Err(format!("boards have {} to {} rows", MIN_SIZE, MAX_ROWS));
+        }
+        if self.columns < MIN_SIZE || self.columns > MAX_COLUMNS {
+            return Err(format!(
+                "boards have {} to {} columns",
+                MIN_SIZE, MAX_COLUMNS
+            ));
+        }
+        if self.connect < MIN_CONNECT || self.connect > MAX_CONNECT {
+            return Err(format!(
+                "lines are {} to {} discs long",
+                MIN_CONNECT, MAX_CONNECT
+            ));
+        }
+        if self.connect > self.rows.max(self.columns) {
+            return Err(format!(
+                "a line of {} doesn't fit on a {}x{} board",
+                self.connect, self.rows, self.columns
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == Variant::STANDARD
+    }
+}
+
+impl Default for Variant {
+    fn default() -> Self {
+        Variant::STANDARD
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}x{}, connect {}",
+            self.rows, self.columns, self.connect
+        )
+    }
+}
+
+// a disc on the board, TOOT-OTTO discs also carry a letter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub player: Player,
+    pub letter: Option<Letter>,
+    // the special disc it is in Power-Up
+    pub power: Option<Power>,
+}
+
+impl Cell {
+    // the player the disc counts for in a line, walls count for neither
+    pub fn owner(&self) -> Option<Player> {
+        match self.power {
+            Some(Power::Wall) => None,
+            _ => Some(self.player),
+        }
+    }
+}
+
+// row 0 is the top of the board, row rows() - 1 is the bottom
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Board {
+    variant: Variant,
+    // row by row from the top
+    cells: Vec<Option<Cell>>,
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Board::with_variant(Variant::STANDARD)
+    }
+
+    pub fn with_variant(variant: Variant) -> Self {
+        Board {
+            variant,
+            cells: vec![None; variant.rows * variant.columns],
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn rows(&self) -> usize {
+        self.variant.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.variant.columns
+    }
+
+    // length of a winning line
+    pub fn connect(&self) -> usize {
+        self.variant.connect
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Option<Cell> {
+        self.cells[row * self.columns() + column]
+    }
+
+    // row a disc dropped into column would land in, None if the column is full
+    pub fn drop_row(&self, column: usize) -> Option<usize> {
+        if column >= self.columns() {
+            return None;
+        }
+        (0..self.rows())
+            .rev()
+            .find(|&row| self.get(row, column).is_none())
+    }
+
+    // row of the top-most disc in column, None if the column is empty
+    pub fn top_row(&self, column: usize) -> Option<usize> {
+        if column >= self.columns() {
+            return None;
+        }
+        (0..self.rows()).find(|&row| self.get(row, column).is_some())
+    }
+
+    pub fn is_full(&self) -> bool {
+        (0..self.columns()).all(|column| self.get(0, column).is_some())
+    }
+
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    pub(crate) fn set(&mut self, row: usize, column: usize, cell: Option<Cell>) {
+        let columns = self.columns();
+        self.cells[row * columns + column] = cell;
+    }
+
+    // discs of player in a row from next to row, column along direction
+    pub(crate) fn run(
+        &self,
+        row: usize,
+        column: usize,
+        (dr, dc): (isize, isize),
+        player: Player,
+    ) -> usize {
+        let mut run = 0;
+        let (mut r, mut c) = (row as isize + dr, column as isize + dc);
+        while self.contains(r, c) {
+            match self.get(r as usize, c as usize) {
+                Some(cell) if cell.owner() == Some(player) => run += 1,
+                _ => break,
+            }
+            r += dr;
+            c += dc;
+        }
+        run
+    }
+
+    // every horizontal, vertical and diagonal run of connect() cells
+    pub fn lines(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut lines = Vec::new();
+        for row in 0..self.rows() {
+            for column in 0..self.columns() {
+                for &direction in DIRECTIONS.iter() {
+                    lines.extend(self.line(row as isize, column as isize, direction));
+                }
+            }
+        }
+        lines
+    }
+
+    // the lines that include the cell at row, column
+    pub fn lines_through(&self, row: usize, column: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut lines = Vec::new();
+        for &(dr, dc) in DIRECTIONS.iter() {
+            // the cell can be at any position along the line
+            for k in 0..self.connect() as isize {
+                let start_row = row as isize - dr * k;
+                let start_column = column as isize - dc * k;
+                lines.extend(self.line(start_row, start_column, (dr, dc)));
+            }
+        }
+        lines
+    }
+
+    fn contains(&self, row: isize, column: isize) -> bool {
+        row >= 0 && row < self.rows() as isize && column >= 0 && column < self.columns() as isize
+    }
+
+    // the connect() cells from row, column along direction, None if it leaves the board
+    fn line(
+        &self,
+        row: isize,
+        column: isize,
+        (dr, dc): (isize, isize),
+    ) -> Option<Vec<(usize, usize)>> {
+        (0..self.connect() as isize)
+            .map(|k| {
+                let (r, c) = (row + dr * k, column + dc * k);
+                if self.contains(r, c) {
+                    Some((r as usize, c as usize))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+// row and column steps along a line
+pub(crate) const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (-1, 1)];
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameKind, GameState, Move, Outcome};
+
+    // drops discs into columns in turn, starting with Player::One
+    fn play(state: &mut GameState, columns: &[usize]) {
+        for &column in columns {
+            state.apply_move(Move::drop(column)).unwrap();
+        }
+    }
+
+    #[test]
+    fn four_in_a_row_wins() {
+        let horizontal = [0, 0, 1, 1, 2, 2, 3];
+        let vertical = [0, 1, 0, 1, 0, 1, 0];
+        let diagonal = [0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3];
+        for columns in [&horizontal[..], &vertical[..], &diagonal[..]].iter() {
+            let mut state = GameState::new(GameKind::Connect4);
+            play(&mut state, &columns[..columns.len() - 1]);
+            assert_eq!(state.outcome(), None);
+            play(&mut state, &columns[columns.len() - 1..]);
+            assert_eq!(state.outcome(), Some(Outcome::Win(Player::One)));
+        }
+    }
+
+    #[test]
+    fn full_board_without_a_line_is_a_draw() {
+        // columns fill two and two from the bottom, so no line of four forms
+        let variant = Variant::new(4, 4, 4).unwrap();
+        let mut state = GameState::with_variant(GameKind::Connect4, variant);
+        let columns = [0, 1, 0, 1, 1, 0, 1, 0, 2, 3, 2, 3, 3, 2, 3, 2];
+        play(&mut state, &columns[..columns.len() - 1]);
+        assert_eq!(state.outcome(), None);
+        play(&mut state, &columns[columns.len() - 1..]);
+        assert!(state.board().is_full());
+        assert_eq!(state.outcome(), Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn undo_restores_the_board() {
+        let mut state = GameState::new(GameKind::Connect4);
+        play(&mut state, &[3, 3, 4]);
+        let before = state.clone();
+        play(&mut state, &[4]);
+        assert_eq!(
+            state.board().get(ROWS - 2, 4).map(|cell| cell.player),
+            Some(Player::Two)
+        );
+
+        assert_eq!(state.undo_move(), Some(Move::drop(4)));
+        assert_eq!(state, before);
+        assert_eq!(state.board().get(ROWS - 2, 4), None);
+
+        // undoing a winning move reopens the game
+        play(&mut state, &[0, 5, 0, 2]);
+        assert!(state.is_over());
+        state.undo_move();
+        assert_eq!(state.outcome(), None);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::board::{Board, DIRECTIONS};
+use crate::game::Outcome;
+
+// connect() discs of the same player in a line wins, a full board without one is a
+// draw; only lines through the disc just played at row, column can have changed.
+// Power-Up walls complete nothing
+pub(crate) fn outcome(board: &Board, row: usize, column: usize) -> Option<Outcome> {
+    if let Some(player) = board.get(row, column).and_then(|cell| cell.owner()) {
+        for &direction in DIRECTIONS.iter() {
+            let backwards = (-direction.0, -direction.1);
+            let line = 1
+                + board.run(row, column, direction, player)
+                + board.run(row, column, backwards, player);
+            if line >= board.connect() {
+                return Some(Outcome::Win(player));
+            }
+        }
+    }
+
+    if board.is_full() {
+        Some(Outcome::Draw)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,856 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Cell, Variant};
+use crate::{connect4, pop_out, toot_otto, zobrist};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameKind {
+    Connect4,
+    TootOtto,
+    // Connect4 where a player may instead take one of their discs out of the bottom row
+    PopOut,
+    // Connect4 where each player has one of every special disc
+    PowerUp,
+    // five in a row on a 6x9 board whose outer columns start full
+    FiveInARow,
+}
+
+impl GameKind {
+    // name used for the game type in saved game records
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameKind::Connect4 => "Connect-4",
+            GameKind::TootOtto => "TOOT-OTTO",
+            GameKind::PopOut => "Pop-Out",
+            GameKind::PowerUp => "Power-Up",
+            GameKind::FiveInARow => "5-in-a-Row",
+        }
+    }
+
+    // the board a game of this kind is played on unless players pick another
+    pub fn variant(&self) -> Variant {
+        match self {
+            GameKind::FiveInARow => Variant::FIVE_IN_A_ROW,
+            _ => Variant::STANDARD,
+        }
+    }
+
+    // TOOT-OTTO and 5-in-a-Row are only played on their own board
+    pub fn has_fixed_board(&self) -> bool {
+        match self {
+            GameKind::TootOtto | GameKind::FiveInARow => true,
+            GameKind::Connect4 | GameKind::PopOut | GameKind::PowerUp => false,
+        }
+    }
+}
+
+impl fmt::Display for GameKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for GameKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Connect-4" => Ok(GameKind::Connect4),
+            "TOOT-OTTO" => Ok(GameKind::TootOtto),
+            "Pop-Out" => Ok(GameKind::PopOut),
+            "Power-Up" => Ok(GameKind::PowerUp),
+            "5-in-a-Row" => Ok(GameKind::FiveInARow),
+            _ => Err(format!("unknown game type {:?}", s)),
+        }
+    }
+}
+
+// Player::One moves first; in TOOT-OTTO they spell TOOT and Player::Two spells OTTO
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn other(self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    // +1 / -1 encoding used by the canvases
+    pub fn value(self) -> i64 {
+        match self {
+            Player::One => 1,
+            Player::Two => -1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Letter {
+    T,
+    O,
+}
+
+impl Letter {
+    pub fn as_char(self) -> char {
+        match self {
+            Letter::T => 'T',
+            Letter::O => 'O',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Letter> {
+        match c {
+            'T' => Some(Letter::T),
+            'O' => Some(Letter::O),
+            _ => None,
+        }
+    }
+}
+
+// Power-Up discs, each player can use every one of them once a game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Power {
+    // crushes every disc in its column and lands at the bottom
+    Anvil,
+    // lands on the top disc of its column and blows up with it
+    Bomb,
+    // lands like a disc but counts for neither player
+    Wall,
+    // lands like a disc and its player moves again
+    Double,
+}
+
+impl Power {
+    pub const ALL: [Power; 4] = [Power::Anvil, Power::Bomb, Power::Wall, Power::Double];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Power::Anvil => "Anvil",
+            Power::Bomb => "Bomb",
+            Power::Wall => "Wall",
+            Power::Double => "x2",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+// letters a TOOT-OTTO player has left to place
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Inventory {
+    #[serde(rename = "T")]
+    pub t: u8,
+    #[serde(rename = "O")]
+    pub o: u8,
+}
+
+impl Inventory {
+    // each player starts with six of each letter
+    pub const START: Inventory = Inventory { t: 6, o: 6 };
+
+    pub fn count(&self, letter: Letter) -> u8 {
+        match letter {
+            Letter::T => self.t,
+            Letter::O => self.o,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.t == 0 && self.o == 0
+    }
+
+    fn count_mut(&mut self, letter: Letter) -> &mut u8 {
+        match letter {
+            Letter::T => &mut self.t,
+            Letter::O => &mut self.o,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Move {
+    pub column: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub letter: Option<Letter>,
+    // a Pop Out move taking the player's own disc out of the bottom of column
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub pop: bool,
+    // a Power-Up move playing a special disc
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power: Option<Power>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Move {
+    // a Connect4 move
+    pub fn drop(column: usize) -> Self {
+        Move {
+            column,
+            letter: None,
+            pop: false,
+            power: None,
+        }
+    }
+
+    // a TOOT-OTTO move
+    pub fn letter(column: usize, letter: Letter) -> Self {
+        Move {
+            column,
+            letter: Some(letter),
+            pop: false,
+            power: None,
+        }
+    }
+
+    // a Pop Out move removing a disc instead of dropping one
+    pub fn pop(column: usize) -> Self {
+        Move {
+            column,
+            letter: None,
+            pop: true,
+            power: None,
+        }
+    }
+
+    // a Power-Up move dropping a special disc
+    pub fn power(column: usize, power: Power) -> Self {
+        Move {
+            column,
+            letter: None,
+            pop: false,
+            power: Some(power),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    GameOver,
+    InvalidColumn(usize),
+    ColumnFull(usize),
+    MissingLetter,
+    UnexpectedLetter,
+    OutOfLetter(Letter),
+    UnexpectedPop,
+    CannotPop(usize),
+    UnexpectedPower,
+    PowerUsed(Power),
+    NothingToBomb(usize),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::GameOver => write!(f, "the game is already over"),
+            MoveError::InvalidColumn(column) => write!(f, "column {} is off the board", column),
+            MoveError::ColumnFull(column) => write!(f, "column {} is full", column),
+            MoveError::MissingLetter => write!(f, "TOOT-OTTO moves need a letter"),
+            MoveError::UnexpectedLetter => write!(f, "Connect4 moves can't have a letter"),
+            MoveError::OutOfLetter(letter) => write!(f, "no {}'s left to place", letter.as_char()),
+            MoveError::UnexpectedPop => write!(f, "only Pop-Out moves can pop a disc"),
+            MoveError::CannotPop(column) => {
+                write!(f, "the bottom disc of column {} isn't yours to pop", column)
+            }
+            MoveError::UnexpectedPower => write!(f, "only Power-Up moves can use a special disc"),
+            MoveError::PowerUsed(power) => write!(f, "the {} has been used already", power.name()),
+            MoveError::NothingToBomb(column) => write!(f, "column {} has no disc to bomb", column),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win(Player),
+    Draw,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameState {
+    kind: GameKind,
+    board: Board,
+    history: Vec<Move>,
+    hash: u64,
+    // letters left for Player::One and Player::Two, TOOT-OTTO only
+    inventories: Option<[Inventory; 2]>,
+    // decided by the last move, no move can follow one that ends the game
+    outcome: Option<Outcome>,
+    // hash before every move, Pop-Out positions can come round again
+    positions: Vec<u64>,
+    // special discs Player::One and Player::Two have used as Power::bit flags,
+    // Power-Up only
+    powers_used: Option<[u8; 2]>,
+    // discs each special disc took off the board, most recent last
+    removed: Vec<Vec<(usize, Cell)>>,
+    // Power::Double moves in the history, after each its player moved again
+    doubles: usize,
+}
+
+impl GameState {
+    pub fn new(kind: GameKind) -> Self {
+        GameState::with_variant(kind, kind.variant())
+    }
+
+    // a game on another board size or line length, for the kinds without a fixed board
+    pub fn with_variant(kind: GameKind, variant: Variant) -> Self {
+        let inventories = match kind {
+            GameKind::TootOtto => Some([Inventory::START; 2]),
+            _ => None,
+        };
+        let powers_used = match kind {
+            GameKind::PowerUp => Some([0; 2]),
+            _ => None,
+        };
+        let mut state = GameState {
+            kind,
+            board: Board::with_variant(variant),
+            history: Vec::new(),
+            hash: 0,
+            inventories,
+            outcome: None,
+            positions: Vec::new(),
+            powers_used,
+            removed: Vec::new(),
+            doubles: 0,
+        };
+        if kind == GameKind::FiveInARow {
+            state.fill_borders();
+        }
+        state
+    }
+
+    pub fn kind(&self) -> GameKind {
+        self.kind
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.board.variant()
+    }
+
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    // letters player has left, None for games without an inventory
+    pub fn inventory(&self, player: Player) -> Option<Inventory> {
+        self.inventories
+            .map(|inventories| inventories[index(player)])
+    }
+
+    // special discs player can still use, none outside Power-Up
+    pub fn powers_left(&self, player: Player) -> Vec<Power> {
+        let used = match self.powers_used {
+            Some(used) => used[index(player)],
+            None => return Vec::new(),
+        };
+        Power::ALL
+            .iter()
+            .cloned()
+            .filter(|power| used & power.bit() == 0)
+            .collect()
+    }
+
+    // Zobrist hash of the board and side to move
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn to_move(&self) -> Player {
+        match (self.history.len() - self.doubles) % 2 {
+            0 => Player::One,
+            _ => Player::Two,
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        if self.outcome().is_some() {
+            return Vec::new();
+        }
+
+        let columns =
+            (0..self.board.columns()).filter(|&column| self.board.drop_row(column).is_some());
+        match self.kind {
+            GameKind::Connect4 | GameKind::FiveInARow => columns.map(Move::drop).collect(),
+            GameKind::PopOut => {
+                let bottom = self.board.rows() - 1;
+                let own = |column: &usize| {
+                    self.board.get(bottom, *column).map(|cell| cell.player) == Some(self.to_move())
+                };
+                let pops = (0..self.board.columns()).filter(own).map(Move::pop);
+                columns.map(Move::drop).chain(pops).collect()
+            }
+            GameKind::PowerUp => {
+                let player = self.to_move();
+                let powers = self.powers_left(player).into_iter().flat_map(|power| {
+                    (0..self.board.columns()).map(move |column| Move::power(column, power))
+                });
+                columns
+                    .map(Move::drop)
+                    .chain(powers.filter(|&mv| self.validate(mv).is_ok()))
+                    .collect()
+            }
+            GameKind::TootOtto => {
+                let inventory = self.inventory(self.to_move()).unwrap();
+                let letters: Vec<Letter> = [Letter::T, Letter::O]
+                    .iter()
+                    .cloned()
+                    .filter(|&letter| inventory.count(letter) > 0)
+                    .collect();
+                columns
+                    .flat_map(|column| {
+                        letters
+                            .iter()
+                            .map(move |&letter| Move::letter(column, letter))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // checks mv against the rules without playing it
+    pub fn validate(&self, mv: Move) -> Result<usize, MoveError> {
+        if self.outcome().is_some() {
+            return Err(MoveError::GameOver);
+        }
+        match (self.kind, mv.letter) {
+            (GameKind::TootOtto, None) => return Err(MoveError::MissingLetter),
+            (GameKind::TootOtto, Some(letter)) => {
+                if let Some(inventory) = self.inventory(self.to_move()) {
+                    if inventory.count(letter) == 0 {
+                        return Err(MoveError::OutOfLetter(letter));
+                    }
+                }
+            }
+            (_, Some(_)) => return Err(MoveError::UnexpectedLetter),
+            (_, None) => {}
+        }
+        if mv.column >= self.board.columns() {
+            return Err(MoveError::InvalidColumn(mv.column));
+        }
+        if let Some(power) = mv.power {
+            if self.kind != GameKind::PowerUp || mv.pop {
+                return Err(MoveError::UnexpectedPower);
+            }
+            if !self.powers_left(self.to_move()).contains(&power) {
+                return Err(MoveError::PowerUsed(power));
+            }
+            match power {
+                Power::Anvil => return Ok(self.board.rows() - 1),
+                Power::Bomb => {
+                    return self
+                        .board
+                        .top_row(mv.column)
+                        .ok_or(MoveError::NothingToBomb(mv.column))
+                }
+                Power::Wall | Power::Double => {}
+            }
+        }
+        if mv.pop {
+            if self.kind != GameKind::PopOut {
+                return Err(MoveError::UnexpectedPop);
+            }
+            let bottom = self.board.rows() - 1;
+            return match self.board.get(bottom, mv.column) {
+                Some(cell) if cell.player == self.to_move() => Ok(bottom),
+                _ => Err(MoveError::CannotPop(mv.column)),
+            };
+        }
+        self.board
+            .drop_row(mv.column)
+            .ok_or(MoveError::ColumnFull(mv.column))
+    }
+
+    // plays mv for the side to move and returns the row the disc landed in, or was
+    // popped or bombed from
+    pub fn apply_move(&mut self, mv: Move) -> Result<usize, MoveError> {
+        let row = self.validate(mv)?;
+        self.positions.push(self.hash);
+        if mv.pop {
+            let player = self.to_move();
+            self.pop_disc(mv.column);
+            self.history.push(mv);
+            self.hash ^= zobrist::SIDE;
+            self.outcome = pop_out::outcome(&self.board, mv.column, player);
+            // a position seen twice before ends the game, popping could go on forever
+            let seen = self
+                .positions
+                .iter()
+                .filter(|&&hash| hash == self.hash)
+                .count();
+            if self.outcome.is_none() && seen >= 2 {
+                self.outcome = Some(Outcome::Draw);
+            }
+            return Ok(row);
+        }
+        if let Some(power) = mv.power {
+            self.apply_power(mv.column, row, power);
+            self.history.push(mv);
+            self.check_full_board();
+            return Ok(row);
+        }
+        let cell = Cell {
+            player: self.to_move(),
+            letter: mv.letter,
+            power: None,
+        };
+        self.board.set(row, mv.column, Some(cell));
+        self.history.push(mv);
+        self.take_letter(cell, -1);
+        self.hash ^= zobrist::cell(row, mv.column, cell) ^ zobrist::SIDE;
+        self.outcome = match self.kind {
+            GameKind::Connect4 | GameKind::PopOut | GameKind::PowerUp | GameKind::FiveInARow => {
+                connect4::outcome(&self.board, row, mv.column)
+            }
+            GameKind::TootOtto => toot_otto::outcome(&self.board, row, mv.column),
+        };
+        // a player without letters can't move, so the game is drawn
+        let out_of_letters = match self.inventory(self.to_move()) {
+            Some(inventory) => inventory.is_empty(),
+            None => false,
+        };
+        if self.outcome.is_none() && out_of_letters {
+            self.outcome = Some(Outcome::Draw);
+        }
+        self.check_full_board();
+        Ok(row)
+    }
+
+    // takes back the last move, returns None at the start of the game
+    pub fn undo_move(&mut self) -> Option<Move> {
+        let mv = self.history.pop()?;
+        self.positions.pop();
+        if mv.pop {
+            // only the player who popped it could take the disc out
+            let cell = Cell {
+                player: self.to_move(),
+                letter: None,
+                power: None,
+            };
+            self.unpop_disc(mv.column, cell);
+            self.hash ^= zobrist::SIDE;
+            self.outcome = None;
+            return Some(mv);
+        }
+        if let Some(power) = mv.power {
+            self.undo_power(mv.column, power);
+            return Some(mv);
+        }
+        let row = self
+            .board
+            .top_row(mv.column)
+            .expect("history and board out of sync");
+        let cell = self.board.get(row, mv.column).unwrap();
+        self.hash ^= zobrist::cell(row, mv.column, cell) ^ zobrist::SIDE;
+        self.board.set(row, mv.column, None);
+        self.take_letter(cell, 1);
+        self.outcome = None;
+        Some(mv)
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self.outcome() {
+            Some(Outcome::Win(player)) => Some(player),
+            _ => None,
+        }
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.outcome() == Some(Outcome::Draw)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    // fills both outer columns with alternating discs, Player::One's at the bottom
+    // left and Player::Two's at the bottom right so every row has one of each
+    fn fill_borders(&mut self) {
+        let (rows, last) = (self.board.rows(), self.board.columns() - 1);
+        for row in 0..rows {
+            let left = if (rows - 1 - row) % 2 == 0 {
+                Player::One
+            } else {
+                Player::Two
+            };
+            for &(column, player) in [(0, left), (last, left.other())].iter() {
+                let cell = Cell {
+                    player,
+                    letter: None,
+                    power: None,
+                };
+                self.replace(row, column, Some(cell));
+            }
+        }
+    }
+
+    // a full board only ends a Pop-Out or Power-Up game once the side to move can't
+    // pop or play a special disc either
+    fn check_full_board(&mut self) {
+        let moves_off_full = matches!(self.kind, GameKind::PopOut | GameKind::PowerUp);
+        if moves_off_full && self.outcome == Some(Outcome::Draw) {
+            self.outcome = None;
+            if self.legal_moves().is_empty() {
+                self.outcome = Some(Outcome::Draw);
+            }
+        }
+    }
+
+    // plays a special disc landing at, or bombing, row of column
+    fn apply_power(&mut self, column: usize, row: usize, power: Power) {
+        let player = self.to_move();
+        let crushed = match power {
+            Power::Anvil => 0..self.board.rows(),
+            Power::Bomb => row..row + 1,
+            Power::Wall | Power::Double => 0..0,
+        };
+        let mut removed = Vec::new();
+        for crushed_row in crushed {
+            if let Some(cell) = self.board.get(crushed_row, column) {
+                removed.push((crushed_row, cell));
+                self.replace(crushed_row, column, None);
+            }
+        }
+        if power != Power::Bomb {
+            let cell = Cell {
+                player,
+                letter: None,
+                power: Some(power),
+            };
+            self.replace(row, column, Some(cell));
+        }
+        self.removed.push(removed);
+        self.toggle_power(player, power);
+        if power == Power::Double {
+            self.doubles += 1;
+        } else {
+            self.hash ^= zobrist::SIDE;
+        }
+        // taking a disc away can't complete a line or fill the board
+        self.outcome = match power {
+            Power::Bomb => None,
+            _ => connect4::outcome(&self.board, row, column),
+        };
+    }
+
+    // takes back a special disc, the last move in the history has been removed
+    fn undo_power(&mut self, column: usize, power: Power) {
+        if power == Power::Double {
+            self.doubles -= 1;
+        } else {
+            self.hash ^= zobrist::SIDE;
+        }
+        let player = self.to_move();
+        match power {
+            Power::Anvil => self.replace(self.board.rows() - 1, column, None),
+            Power::Bomb => {}
+            Power::Wall | Power::Double => {
+                let row = self
+                    .board
+                    .top_row(column)
+                    .expect("history and board out of sync");
+                self.replace(row, column, None);
+            }
+        }
+        for (row, cell) in self.removed.pop().expect("history and board out of sync") {
+            self.replace(row, column, Some(cell));
+        }
+        self.toggle_power(player, power);
+        self.outcome = None;
+    }
+
+    // marks power used by player, or unused again
+    fn toggle_power(&mut self, player: Player, power: Power) {
+        if let Some(used) = self.powers_used.as_mut() {
+            used[index(player)] ^= power.bit();
+            self.hash ^= zobrist::power(player, power);
+        }
+    }
+
+    // moves every disc in column down a row, the bottom one leaves the board
+    fn pop_disc(&mut self, column: usize) {
+        for row in (0..self.board.rows()).rev() {
+            let above = if row == 0 {
+                None
+            } else {
+                self.board.get(row - 1, column)
+            };
+            self.replace(row, column, above);
+        }
+    }
+
+    // moves every disc in column up a row and puts cell back at the bottom
+    fn unpop_disc(&mut self, column: usize, cell: Cell) {
+        let bottom = self.board.rows() - 1;
+        for row in 0..=bottom {
+            let below = if row == bottom {
+                Some(cell)
+            } else {
+                self.board.get(row + 1, column)
+            };
+            self.replace(row, column, below);
+        }
+    }
+
+    // sets a cell, keeping the hash up to date
+    fn replace(&mut self, row: usize, column: usize, cell: Option<Cell>) {
+        if let Some(old) = self.board.get(row, column) {
+            self.hash ^= zobrist::cell(row, column, old);
+        }
+        if let Some(new) = cell {
+            self.hash ^= zobrist::cell(row, column, new);
+        }
+        self.board.set(row, column, cell);
+    }
+
+    // adds change to the inventory of the player who owns cell
+    fn take_letter(&mut self, cell: Cell, change: i8) {
+        if let (Some(inventories), Some(letter)) = (self.inventories.as_mut(), cell.letter) {
+            let count = inventories[index(cell.player)].count_mut(letter);
+            *count = (*count as i8 + change) as u8;
+        }
+    }
+}
+
+fn index(player: Player) -> usize {
+    match player {
+        Player::One => 0,
+        Player::Two => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // xorshift, enough to pick moves without another dependency
+    fn next(seed: &mut u64, n: usize) -> usize {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        (*seed % n as u64) as usize
+    }
+
+    // plays random games, undoing and replaying every move on the way, then unwinds
+    // each game back to the start
+    fn round_trip(kind: GameKind, games: usize) {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..games {
+            let mut state = GameState::new(kind);
+            let mut before = Vec::new();
+            while !state.is_over() {
+                let moves = state.legal_moves();
+                let mv = moves[next(&mut seed, moves.len())];
+                let saved = state.clone();
+                state.apply_move(mv).unwrap();
+                state.undo_move();
+                assert_eq!(state, saved, "undoing {:?} changed the game", mv);
+                state.apply_move(mv).unwrap();
+                before.push(saved);
+            }
+
+            let mut replayed = GameState::new(kind);
+            for &mv in state.history() {
+                replayed.apply_move(mv).unwrap();
+            }
+            assert_eq!(replayed.hash(), state.hash());
+
+            while let Some(saved) = before.pop() {
+                state.undo_move();
+                assert_eq!(state, saved);
+                assert_eq!(state.hash(), saved.hash());
+            }
+            assert_eq!(state.hash(), GameState::new(kind).hash());
+        }
+    }
+
+    #[test]
+    fn pop_out_undo_and_hash_round_trip() {
+        round_trip(GameKind::PopOut, 200);
+    }
+
+    #[test]
+    fn power_up_undo_and_hash_round_trip() {
+        round_trip(GameKind::PowerUp, 200);
+    }
+
+    #[test]
+    fn full_pop_out_board_plays_on() {
+        // fills a 4x4 board without a line, Player::One owns the bottom of columns 0 and 2
+        let variant = Variant::new(4, 4, 4).unwrap();
+        let mut state = GameState::with_variant(GameKind::PopOut, variant);
+        for &column in [0, 1, 0, 1, 1, 0, 1, 0, 2, 3, 2, 3, 3, 2, 3, 2].iter() {
+            state.apply_move(Move::drop(column)).unwrap();
+        }
+        assert!(state.board().is_full());
+        assert_eq!(state.outcome(), None);
+        assert_eq!(state.legal_moves(), vec![Move::pop(0), Move::pop(2)]);
+        state.apply_move(Move::pop(0)).unwrap();
+        assert_eq!(state.validate(Move::drop(0)), Ok(0));
+    }
+
+    #[test]
+    fn full_power_up_board_plays_on() {
+        let variant = Variant::new(4, 4, 4).unwrap();
+        let mut state = GameState::with_variant(GameKind::PowerUp, variant);
+        for &column in [0, 1, 0, 1, 1, 0, 1, 0, 2, 3, 2, 3, 3, 2, 3, 2].iter() {
+            state.apply_move(Move::drop(column)).unwrap();
+        }
+        assert!(state.board().is_full());
+        assert_eq!(state.outcome(), None);
+        assert!(state.legal_moves().contains(&Move::power(1, Power::Anvil)));
+        assert!(state.legal_moves().contains(&Move::power(1, Power::Bomb)));
+        assert!(!state.legal_moves().contains(&Move::power(1, Power::Wall)));
+        state.apply_move(Move::power(1, Power::Anvil)).unwrap();
+        assert_eq!(state.board().drop_row(1), Some(2));
+    }
+
+    #[test]
+    fn power_up_draws_once_nothing_can_be_played() {
+        let variant = Variant::new(4, 4, 4).unwrap();
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        let mut draws = 0;
+        for _ in 0..500 {
+            let mut state = GameState::with_variant(GameKind::PowerUp, variant);
+            while !state.is_over() {
+                let moves = state.legal_moves();
+                state
+                    .apply_move(moves[next(&mut seed, moves.len())])
+                    .unwrap();
+            }
+            if state.is_draw() {
+                // a Wall or Double has nowhere to land, an Anvil or Bomb always has
+                let left = state.powers_left(state.to_move());
+                assert!(state.board().is_full());
+                assert!(!left.contains(&Power::Anvil) && !left.contains(&Power::Bomb));
+                draws += 1;
+            }
+        }
+        assert!(draws > 0);
+    }
+}
@@ -0,0 +1,19 @@
+//! UI-free rules for Connect4, Connect-N, Pop-Out, Power-Up, 5-in-a-Row and TOOT-OTTO, shared by the frontend and backend.
+
+pub mod ai;
+mod bitboard;
+mod board;
+mod connect4;
+mod game;
+mod pop_out;
+mod toot_otto;
+mod zobrist;
+
+pub use crate::{
+    bitboard::Position,
+    board::{
+        Board, Cell, Variant, COLUMNS, CONNECT, MAX_COLUMNS, MAX_CONNECT, MAX_ROWS, MIN_CONNECT,
+        MIN_SIZE, ROWS,
+    },
+    game::{GameKind, GameState, Inventory, Letter, Move, MoveError, Outcome, Player, Power},
+};
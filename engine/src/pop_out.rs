@@ -0,0 +1,34 @@
+use crate::board::{Board, DIRECTIONS};
+use crate::game::{Outcome, Player};
+
+// popping moves every disc in column, so any of them can complete a line and both
+// players can be left with one at once, the player who popped wins then. Drops are
+// decided as in Connect4
+pub(crate) fn outcome(board: &Board, column: usize, player: Player) -> Option<Outcome> {
+    let (mut own, mut other) = (false, false);
+    for row in 0..board.rows() {
+        let owner = match board.get(row, column).and_then(|cell| cell.owner()) {
+            Some(owner) => owner,
+            None => continue,
+        };
+        for &direction in DIRECTIONS.iter() {
+            let backwards = (-direction.0, -direction.1);
+            let line = 1
+                + board.run(row, column, direction, owner)
+                + board.run(row, column, backwards, owner);
+            if line >= board.connect() {
+                if owner == player {
+                    own = true;
+                } else {
+                    other = true;
+                }
+            }
+        }
+    }
+
+    match (own, other) {
+        (true, _) => Some(Outcome::Win(player)),
+        (false, true) => Some(Outcome::Win(player.other())),
+        (false, false) => None,
+    }
+}
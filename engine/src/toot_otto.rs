@@ -0,0 +1,35 @@
+use crate::board::{Board, CONNECT};
+use crate::game::{Letter, Outcome, Player};
+
+pub(crate) const TOOT: [Letter; CONNECT] = [Letter::T, Letter::O, Letter::O, Letter::T];
+pub(crate) const OTTO: [Letter; CONNECT] = [Letter::O, Letter::T, Letter::T, Letter::O];
+
+// whether the letters along line spell word, empty cells spell nothing
+pub(crate) fn spells(board: &Board, line: &[(usize, usize)], word: &[Letter]) -> bool {
+    line.len() == word.len()
+        && line
+            .iter()
+            .zip(word.iter())
+            .all(|(&(row, column), &letter)| {
+                board.get(row, column).and_then(|cell| cell.letter) == Some(letter)
+            })
+}
+
+// TOOT wins for Player::One and OTTO for Player::Two regardless of who placed the
+// letters; both words appearing at once, or a full board, is a draw. Only lines
+// through the letter just played at row, column can have changed
+pub(crate) fn outcome(board: &Board, row: usize, column: usize) -> Option<Outcome> {
+    let (mut toot, mut otto) = (false, false);
+    for line in board.lines_through(row, column) {
+        toot |= spells(board, &line, &TOOT);
+        otto |= spells(board, &line, &OTTO);
+    }
+
+    match (toot, otto) {
+        (true, true) => Some(Outcome::Draw),
+        (true, false) => Some(Outcome::Win(Player::One)),
+        (false, true) => Some(Outcome::Win(Player::Two)),
+        (false, false) if board.is_full() => Some(Outcome::Draw),
+        (false, false) => None,
+    }
+}
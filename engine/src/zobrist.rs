@@ -0,0 +1,79 @@
+use crate::board::{Cell, MAX_COLUMNS, MAX_ROWS};
+use crate::game::{Letter, Player, Power};
+
+// a disc is one of the two players, with no letter, a T or an O, or a Power-Up wall
+// that belongs to neither
+const PIECES: usize = 7;
+
+const WALL: usize = 6;
+
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+const KEYS: [[u64; PIECES]; MAX_ROWS * MAX_COLUMNS] = {
+    let mut keys = [[0; PIECES]; MAX_ROWS * MAX_COLUMNS];
+    let mut state = SEED;
+    let mut cell = 0;
+    while cell < MAX_ROWS * MAX_COLUMNS {
+        let mut piece = 0;
+        while piece < PIECES {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            keys[cell][piece] = mix(state);
+            piece += 1;
+        }
+        cell += 1;
+    }
+    keys
+};
+
+// toggled when a player uses up a special disc
+const POWERS: [[u64; 4]; 2] = {
+    let mut keys = [[0; 4]; 2];
+    let mut state = SEED ^ 0x94d0_49bb_1331_11eb;
+    let mut player = 0;
+    while player < 2 {
+        let mut power = 0;
+        while power < 4 {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            keys[player][power] = mix(state);
+            power += 1;
+        }
+        player += 1;
+    }
+    keys
+};
+
+// toggled whenever the side to move changes
+pub const SIDE: u64 = mix(SEED ^ 0xd6e8_feb8_6659_fd93);
+
+// splitmix64 finaliser, spreads sequential seeds over the whole range
+const fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// key for a disc at a cell, row 0 is the top of the board
+pub fn cell(row: usize, column: usize, cell: Cell) -> u64 {
+    if cell.owner().is_none() {
+        return KEYS[row * MAX_COLUMNS + column][WALL];
+    }
+    let player = match cell.player {
+        Player::One => 0,
+        Player::Two => 3,
+    };
+    let letter = match cell.letter {
+        None => 0,
+        Some(Letter::T) => 1,
+        Some(Letter::O) => 2,
+    };
+    KEYS[row * MAX_COLUMNS + column][player + letter]
+}
+
+// key for player having used power
+pub fn power(player: Player, power: Power) -> u64 {
+    let player = match player {
+        Player::One => 0,
+        Player::Two => 1,
+    };
+    POWERS[player][power as usize]
+}
@@ -0,0 +1,168 @@
+use anyhow::Error;
+use serde_json::json;
+use yew::format::{Json, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::{html, Component, ComponentLink, Html, InputData, ShouldRender};
+
+use crate::session::{fetch_account, Account};
+
+pub enum Msg {
+    SessionReady(Option<String>),
+    UpdateName(InputData),
+    UpdatePassword(InputData),
+    Register,
+    LogIn,
+    LoggedIn(Account),
+    Failed(String),
+    LogOut,
+    LoggedOut,
+}
+
+// registration, login and the logged in player's account
+pub struct AccountModel {
+    account: Option<String>,
+    // the session hasn't been fetched yet
+    loading: bool,
+    name: String,
+    password: String,
+    status: String,
+    fetch_service: FetchService,
+    fetch_task: Option<FetchTask>,
+    link: ComponentLink<AccountModel>,
+}
+
+impl AccountModel {
+    fn send_credentials(&mut self, url: &str) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Account, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(account) if meta.status.is_success() => Msg::LoggedIn(account),
+                    _ => Msg::Failed(match meta.status.as_u16() {
+                        401 => String::from("Wrong name or password"),
+                        409 => String::from("That name is already taken"),
+                        422 => String::from(
+                            "Names need 3 to 20 letters, digits, spaces, _ or -, passwords at least 8 characters",
+                        ),
+                        _ => String::from("Couldn't reach the server"),
+                    }),
+                }
+            });
+        let body = json!({ "name": self.name.trim(), "password": self.password });
+        let request = Request::post(url)
+            .header("Content-Type", "application/json")
+            .body(Json(&body))
+            .unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn log_out(&mut self) -> FetchTask {
+        let callback = self
+            .link
+            .callback(|response: Response<Nothing>| Msg::LoggedOut);
+        let request = Request::delete("/session").body(Nothing).unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn view_forms(&self) -> Html {
+        let incomplete = self.name.trim().is_empty() || self.password.is_empty();
+        html! {
+            <div>
+                <input
+                    type="text",
+                    placeholder="Name",
+                    oninput=self.link.callback(|e: InputData| Msg::UpdateName(e)),
+                />
+                <input
+                    type="password",
+                    placeholder="Password",
+                    oninput=self.link.callback(|e: InputData| Msg::UpdatePassword(e)),
+                />
+                <button onclick=self.link.callback(|_| Msg::LogIn) disabled={incomplete}>{ "Log In" }</button>
+                <button onclick=self.link.callback(|_| Msg::Register) disabled={incomplete}>{ "Register" }</button>
+                <p>{ &self.status }</p>
+            </div>
+        }
+    }
+
+    fn view_account(&self, name: &str) -> Html {
+        html! {
+            <div>
+                <h4>{ format!("Logged in as {}", name) }</h4>
+                <p>{ "Games you start are recorded under this name, nobody else can play as it." }</p>
+                <button onclick=self.link.callback(|_| Msg::LogOut)>{ "Log Out" }</button>
+            </div>
+        }
+    }
+}
+
+impl Component for AccountModel {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut model = AccountModel {
+            account: None,
+            loading: true,
+            name: String::new(),
+            password: String::new(),
+            status: String::new(),
+            fetch_service: FetchService::new(),
+            fetch_task: None,
+            link,
+        };
+        model.fetch_task = Some(fetch_account(&mut model.fetch_service, &model.link, Msg::SessionReady));
+        model
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::SessionReady(account) => {
+                self.account = account;
+                self.loading = false;
+                self.fetch_task = None;
+            }
+            Msg::UpdateName(e) => self.name = e.value,
+            Msg::UpdatePassword(e) => self.password = e.value,
+            Msg::Register => self.fetch_task = Some(self.send_credentials("/accounts")),
+            Msg::LogIn => self.fetch_task = Some(self.send_credentials("/session")),
+            Msg::LoggedIn(account) => {
+                self.account = Some(account.name);
+                self.password = String::new();
+                self.status = String::new();
+                self.fetch_task = None;
+            }
+            Msg::Failed(status) => {
+                self.status = status;
+                self.fetch_task = None;
+            }
+            Msg::LogOut => self.fetch_task = Some(self.log_out()),
+            Msg::LoggedOut => {
+                self.account = None;
+                self.fetch_task = None;
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class="w3-container" id="services" style="margin-top:75px">
+            <h5 class="w3-xxxlarge w3-text-red"><b>{"Account"}</b></h5>
+            <hr style="width:50px;border:5px solid red" class="w3-round"></hr>
+            {
+                match &self.account {
+                    Some(name) => self.view_account(name),
+                    None if self.loading => html! { <p>{"Loading..."}</p> },
+                    None => self.view_forms(),
+                }
+            }
+            </div>
+        }
+    }
+}
@@ -1,24 +1,54 @@
 use crate::canvas::CanvasModel;
 use crate::player::Player;
+use crate::saved_game::{self, SavedGame};
+use crate::session::fetch_account;
+use serde::{Deserialize, Serialize};
+use yew::services::fetch::{FetchService, FetchTask};
 use yew::{prelude::*, components::Select, virtual_dom::VNode, Properties};
 
+use engine::{GameKind, Variant, MAX_COLUMNS, MAX_CONNECT, MAX_ROWS, MIN_CONNECT, MIN_SIZE};
+
+// the board on this page, also the key its unfinished game is saved under
+const CANVAS_ID: &str = "connect_computer";
+
 pub struct Connect4ComputerModel {
     player: Player,
     difficulty: Difficulty,
+    // Connect-4, Pop-Out, Power-Up or 5-in-a-Row
+    kind: GameKind,
+    // board size and line length, checked together by variant and unused by kinds
+    // with a fixed board
+    rows: usize,
+    columns: usize,
+    connect: usize,
     update_player_name: Callback<InputData>,
     update_difficulty: Callback<Difficulty>,
+    update_kind: Callback<GameKind>,
+    update_rows: Callback<usize>,
+    update_columns: Callback<usize>,
+    update_connect: Callback<usize>,
     start_game_callback: Callback<ClickEvent>,
+    resume_game_callback: Callback<ClickEvent>,
     end_game_callback: Callback<i64>,
     is_game_on: bool,
     disabled: bool,
     display_state: String,
+    fetch_service: FetchService,
+    session_task: Option<FetchTask>,
+    // unfinished game from an earlier visit, offered until a new game starts
+    saved: Option<SavedGame>,
+    // the saved game the board picks up from
+    resume: Option<SavedGame>,
+    // logged in player, who always plays as player
+    account: Option<String>,
 }
 
-#[derive(PartialEq, Copy, Clone, Debug)]
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     Medium,
     Hard,
+    Perfect,
 }
 use Difficulty::*;
 
@@ -28,6 +58,7 @@ impl ToString for Difficulty {
             Easy => String::from("Easy"),
             Medium => String::from("Medium"),
             Hard => String::from("Hard"),
+            Perfect => String::from("Perfect"),
         }
     }
 }
@@ -35,8 +66,70 @@ impl ToString for Difficulty {
 pub enum Msg {
     NewPlayer(InputData),
     ChangeDifficulty(Difficulty),
+    ChangeKind(GameKind),
+    ChangeRows(usize),
+    ChangeColumns(usize),
+    ChangeConnect(usize),
     StartGame,
+    ResumeGame,
     EndGame,
+    SessionReady(Option<String>),
+}
+
+impl Connect4ComputerModel {
+    fn variant(&self) -> Result<Variant, String> {
+        if self.kind.has_fixed_board() {
+            return Ok(self.kind.variant());
+        }
+        Variant::new(self.rows, self.columns, self.connect)
+    }
+
+    fn view_rules(&self) -> Html {
+        let error = match self.variant() {
+            Ok(_) => String::new(),
+            Err(error) => format!("Can't play that board: {}", error),
+        };
+        html! {
+            <div>
+                {"Rules "}
+                <Select<GameKind>
+                    disabled = { self.disabled }
+                    selected = Some(self.kind),
+                    options = { vec![GameKind::Connect4, GameKind::PopOut, GameKind::PowerUp, GameKind::FiveInARow] }
+                    onchange = &self.update_kind />
+                {" Rows "}
+                <Select<usize>
+                    disabled = { self.disabled || self.kind.has_fixed_board() }
+                    selected = Some(self.rows),
+                    options = { (MIN_SIZE..=MAX_ROWS).collect::<Vec<_>>() }
+                    onchange = &self.update_rows />
+                {" Columns "}
+                <Select<usize>
+                    disabled = { self.disabled || self.kind.has_fixed_board() }
+                    selected = Some(self.columns),
+                    options = { (MIN_SIZE..=MAX_COLUMNS).collect::<Vec<_>>() }
+                    onchange = &self.update_columns />
+                {" In a Row "}
+                <Select<usize>
+                    disabled = { self.disabled || self.kind.has_fixed_board() }
+                    selected = Some(self.connect),
+                    options = { (MIN_CONNECT..=MAX_CONNECT).collect::<Vec<_>>() }
+                    onchange = &self.update_connect />
+                <p>{ error }</p>
+            </div>
+        }
+    }
+
+    fn view_resume(&self) -> Html {
+        match &self.saved {
+            Some(saved) if !self.is_game_on => html! {
+                <button onclick=&self.resume_game_callback title="Resume Game">
+                    { format!("Resume {} Vs {} ({} moves in)", saved.player1, saved.player2, saved.moves.len()) }
+                </button>
+            },
+            _ => html! {},
+        }
+    }
 }
 
 impl Component for Connect4ComputerModel {
@@ -48,16 +141,33 @@ impl Component for Connect4ComputerModel {
             value: "".to_string(),
         };
 
+        let mut fetch_service = FetchService::new();
+        let session_task = fetch_account(&mut fetch_service, &link, Msg::SessionReady);
+
         Connect4ComputerModel {
             player,
             difficulty: Easy,
+            kind: GameKind::Connect4,
+            rows: Variant::STANDARD.rows,
+            columns: Variant::STANDARD.columns,
+            connect: Variant::STANDARD.connect,
             update_player_name: link.callback(|e: InputData| Msg::NewPlayer(e)),
             update_difficulty: link.callback(|e: Difficulty| Msg::ChangeDifficulty(e)),
+            update_kind: link.callback(|e: GameKind| Msg::ChangeKind(e)),
+            update_rows: link.callback(|e: usize| Msg::ChangeRows(e)),
+            update_columns: link.callback(|e: usize| Msg::ChangeColumns(e)),
+            update_connect: link.callback(|e: usize| Msg::ChangeConnect(e)),
             start_game_callback: link.callback(|e| Msg::StartGame),
+            resume_game_callback: link.callback(|e| Msg::ResumeGame),
             end_game_callback: link.callback(|e: i64| Msg::EndGame),
             is_game_on: false,
             disabled: false,
             display_state: "none".to_string(),
+            fetch_service,
+            session_task: Some(session_task),
+            saved: saved_game::load(CANVAS_ID),
+            resume: None,
+            account: None,
         }
     }
 
@@ -68,15 +178,55 @@ impl Component for Connect4ComputerModel {
                 self.difficulty = data
                 // update canvas
             }
+            Msg::ChangeKind(kind) => self.kind = kind,
+            Msg::ChangeRows(rows) => self.rows = rows,
+            Msg::ChangeColumns(columns) => self.columns = columns,
+            Msg::ChangeConnect(connect) => self.connect = connect,
             Msg::StartGame => {
+                saved_game::clear(CANVAS_ID);
+                self.saved = None;
+                self.resume = None;
                 self.is_game_on = true;
                 self.disabled = true;
                 self.display_state = "block".to_string();
             }
+            Msg::ResumeGame => {
+                if let Some(saved) = self.saved.take() {
+                    if self.account.is_none() {
+                        self.player.value = saved.player1.clone();
+                    }
+                    self.difficulty = saved.difficulty;
+                    self.kind = if saved.pop_out {
+                        GameKind::PopOut
+                    } else if saved.power_up {
+                        GameKind::PowerUp
+                    } else if saved.five_in_a_row {
+                        GameKind::FiveInARow
+                    } else {
+                        GameKind::Connect4
+                    };
+                    self.rows = saved.variant.rows;
+                    self.columns = saved.variant.columns;
+                    self.connect = saved.variant.connect;
+                    self.resume = Some(saved);
+                    self.is_game_on = true;
+                    self.disabled = true;
+                    self.display_state = "block".to_string();
+                }
+            }
+            Msg::SessionReady(account) => {
+                if let Some(name) = &account {
+                    self.player.value = name.clone();
+                }
+                self.account = account;
+                self.session_task = None;
+            }
             Msg::EndGame => {
                 self.is_game_on = false;
                 self.disabled = false;
                 self.display_state = "none".to_string();
+                self.resume = None;
+                self.saved = saved_game::load(CANVAS_ID);
             }
         }
 
@@ -100,32 +250,41 @@ impl Component for Connect4ComputerModel {
                         id="player_name",
                         type="text",
                         placeholder="Your Name",
+                        value = &self.player.value,
+                        disabled = { self.account.is_some() },
                         oninput = &self.update_player_name,
                     />
                     <Select<Difficulty> 
                         disabled = { self.disabled }
-                        selected = Some(Easy),
-                        options = { vec![Easy, Medium, Hard] }
+                        selected = Some(self.difficulty),
+                        options = { vec![Easy, Medium, Hard, Perfect] }
                         onchange = &self.update_difficulty />
                     <button
                         id="startbutton",
                         onclick=&self.start_game_callback,
-                        disabled={self.disabled},
+                        disabled={self.disabled || self.variant().is_err()},
                         title="Start Game">
                     { "Start Game" }
                     </button>
+                    { self.view_resume() }
+                    { self.view_rules() }
                 </div>
             </div>
             <div style=format!("display: {}", self.display_state)>
                 <br></br>
-                <h4>{format!("New Game: {} Vs Computer", self.player.value)}</h4>
+                <h4>{format!("New {} Game: {} Vs Computer ({})", self.kind, self.player.value, self.variant().unwrap_or_default())}</h4>
                 <small>{format!("(Disc Colors: {} - ", self.player.value)} <b>{"Red"}</b> {"   and    Computer - "} <b>{"Yellow)"}</b></small>
                 <br></br>
                 <CanvasModel  
-                    canvas_id = "connect_computer" 
+                    canvas_id = CANVAS_ID,
                     player1 = self.player.value.clone(), 
                     player2 = "Computer" 
                     difficulty = self.difficulty,
+                    resume = self.resume.clone(),
+                    variant = self.variant().unwrap_or_default(),
+                    pop_out = self.kind == GameKind::PopOut,
+                    power_up = self.kind == GameKind::PowerUp,
+                    five_in_a_row = self.kind == GameKind::FiveInARow,
                     game_done_cbk=&self.end_game_callback/>
             </div>
             </>
@@ -1,27 +1,117 @@
 use crate::canvas::CanvasModel;
-use yew::{prelude::*, virtual_dom::VNode, Properties};
+use yew::services::fetch::{FetchService, FetchTask};
+use yew::{prelude::*, components::Select, virtual_dom::VNode, Properties};
+
+use engine::{GameKind, Variant, MAX_COLUMNS, MAX_CONNECT, MAX_ROWS, MIN_CONNECT, MIN_SIZE};
 
 use crate::player::Player;
+use crate::saved_game::{self, SavedGame};
+use crate::session::fetch_account;
 use crate::Connect4Computer::Difficulty::Easy;
 
+// the board on this page, also the key its unfinished game is saved under
+const CANVAS_ID: &str = "connect_human";
+
 pub struct Connect4HumanModel {
     player1: Player,
     player2: Player,
+    // Connect-4, Pop-Out, Power-Up or 5-in-a-Row
+    kind: GameKind,
+    // board size and line length, checked together by variant and unused by kinds
+    // with a fixed board
+    rows: usize,
+    columns: usize,
+    connect: usize,
     update_player1_name: Callback<InputData>,
     update_player2_name: Callback<InputData>,
+    update_kind: Callback<GameKind>,
+    update_rows: Callback<usize>,
+    update_columns: Callback<usize>,
+    update_connect: Callback<usize>,
     start_game_callback: Callback<ClickEvent>,
+    resume_game_callback: Callback<ClickEvent>,
     end_game_callback: Callback<i64>,
     is_game_on: bool,
     disabled: bool,
     display_state: String,
+    fetch_service: FetchService,
+    session_task: Option<FetchTask>,
+    // unfinished game from an earlier visit, offered until a new game starts
+    saved: Option<SavedGame>,
+    // the saved game the board picks up from
+    resume: Option<SavedGame>,
+    // logged in player, who always plays as player1
+    account: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum Msg {
     NewPlayer1(InputData),
     NewPlayer2(InputData),
+    ChangeKind(GameKind),
+    ChangeRows(usize),
+    ChangeColumns(usize),
+    ChangeConnect(usize),
     StartGame,
+    ResumeGame,
     EndGame,
+    SessionReady(Option<String>),
+}
+
+impl Connect4HumanModel {
+    fn variant(&self) -> Result<Variant, String> {
+        if self.kind.has_fixed_board() {
+            return Ok(self.kind.variant());
+        }
+        Variant::new(self.rows, self.columns, self.connect)
+    }
+
+    fn view_rules(&self) -> Html {
+        let error = match self.variant() {
+            Ok(_) => String::new(),
+            Err(error) => format!("Can't play that board: {}", error),
+        };
+        html! {
+            <div>
+                {"Rules "}
+                <Select<GameKind>
+                    disabled = { self.disabled }
+                    selected = Some(self.kind),
+                    options = { vec![GameKind::Connect4, GameKind::PopOut, GameKind::PowerUp, GameKind::FiveInARow] }
+                    onchange = &self.update_kind />
+                {" Rows "}
+                <Select<usize>
+                    disabled = { self.disabled || self.kind.has_fixed_board() }
+                    selected = Some(self.rows),
+                    options = { (MIN_SIZE..=MAX_ROWS).collect::<Vec<_>>() }
+                    onchange = &self.update_rows />
+                {" Columns "}
+                <Select<usize>
+                    disabled = { self.disabled || self.kind.has_fixed_board() }
+                    selected = Some(self.columns),
+                    options = { (MIN_SIZE..=MAX_COLUMNS).collect::<Vec<_>>() }
+                    onchange = &self.update_columns />
+                {" In a Row "}
+                <Select<usize>
+                    disabled = { self.disabled || self.kind.has_fixed_board() }
+                    selected = Some(self.connect),
+                    options = { (MIN_CONNECT..=MAX_CONNECT).collect::<Vec<_>>() }
+                    onchange = &self.update_connect />
+                <p>{ error }</p>
+            </div>
+        }
+    }
+
+    fn view_resume(&self) -> Html {
+        match &self.saved {
+            Some(saved) if !self.is_game_on => html! {
+                <button onclick=&self.resume_game_callback title="Resume Game">
+                    { format!("Resume {} Vs {} ({} moves in)", saved.player1, saved.player2, saved.moves.len()) }
+                </button>
+            },
+            _ => html! {},
+        }
+    }
 }
 
 impl Component for Connect4HumanModel {
@@ -37,16 +127,33 @@ impl Component for Connect4HumanModel {
             value: "".to_string(),
         };
 
+        let mut fetch_service = FetchService::new();
+        let session_task = fetch_account(&mut fetch_service, &link, Msg::SessionReady);
+
         Self {
             player1,
             player2,
+            kind: GameKind::Connect4,
+            rows: Variant::STANDARD.rows,
+            columns: Variant::STANDARD.columns,
+            connect: Variant::STANDARD.connect,
             update_player1_name: link.callback(|e: InputData| Msg::NewPlayer1(e)),
             update_player2_name: link.callback(|e: InputData| Msg::NewPlayer2(e)),
+            update_kind: link.callback(|e: GameKind| Msg::ChangeKind(e)),
+            update_rows: link.callback(|e: usize| Msg::ChangeRows(e)),
+            update_columns: link.callback(|e: usize| Msg::ChangeColumns(e)),
+            update_connect: link.callback(|e: usize| Msg::ChangeConnect(e)),
             start_game_callback: link.callback(|e| Msg::StartGame),
+            resume_game_callback: link.callback(|e| Msg::ResumeGame),
             end_game_callback: link.callback(|e: i64| Msg::EndGame),
             is_game_on: false,
             disabled: false,
             display_state: "none".to_string(),
+            fetch_service,
+            session_task: Some(session_task),
+            saved: saved_game::load(CANVAS_ID),
+            resume: None,
+            account: None,
         }
     }
 
@@ -54,15 +161,55 @@ impl Component for Connect4HumanModel {
         match msg {
             Msg::NewPlayer1(val) => self.player1.value = val.value,
             Msg::NewPlayer2(val) => self.player2.value = val.value,
+            Msg::ChangeKind(kind) => self.kind = kind,
+            Msg::ChangeRows(rows) => self.rows = rows,
+            Msg::ChangeColumns(columns) => self.columns = columns,
+            Msg::ChangeConnect(connect) => self.connect = connect,
             Msg::StartGame => {
+                saved_game::clear(CANVAS_ID);
+                self.saved = None;
+                self.resume = None;
                 self.is_game_on = true;
                 self.disabled = true;
                 self.display_state = "block".to_string();
             }
+            Msg::ResumeGame => {
+                if let Some(saved) = self.saved.take() {
+                    if self.account.is_none() {
+                        self.player1.value = saved.player1.clone();
+                    }
+                    self.player2.value = saved.player2.clone();
+                    self.kind = if saved.pop_out {
+                        GameKind::PopOut
+                    } else if saved.power_up {
+                        GameKind::PowerUp
+                    } else if saved.five_in_a_row {
+                        GameKind::FiveInARow
+                    } else {
+                        GameKind::Connect4
+                    };
+                    self.rows = saved.variant.rows;
+                    self.columns = saved.variant.columns;
+                    self.connect = saved.variant.connect;
+                    self.resume = Some(saved);
+                    self.is_game_on = true;
+                    self.disabled = true;
+                    self.display_state = "block".to_string();
+                }
+            }
+            Msg::SessionReady(account) => {
+                if let Some(name) = &account {
+                    self.player1.value = name.clone();
+                }
+                self.account = account;
+                self.session_task = None;
+            }
             Msg::EndGame => {
                 self.is_game_on = false;
                 self.disabled = false;
                 self.display_state = "none".to_string();
+                self.resume = None;
+                self.saved = saved_game::load(CANVAS_ID);
             }
         }
 
@@ -86,34 +233,44 @@ impl Component for Connect4HumanModel {
                         id="textbox1",
                         type="text",
                         placeholder="Player 1's Name",
+                        value = &self.player1.value,
+                        disabled = { self.account.is_some() },
                         oninput = &self.update_player1_name,
                     />
                     <input
                         id="textbox2",
                         type="text",
                         placeholder="Player 2's Name",
+                        value = &self.player2.value,
                         oninput = &self.update_player2_name,
                     />
                     <button
                     id="startbutton",
                     onclick=&self.start_game_callback,
-                    disabled={self.disabled},
+                    disabled={self.disabled || self.variant().is_err()},
                     title="Start Game",
                     >
                     { "Start Game" }
                     </button>
+                    { self.view_resume() }
+                    { self.view_rules() }
                 </div>
             </div>
             <div style=format!("display: {}", self.display_state)>
                 <br></br>
-                <h4>{format!("New Game: {} Vs {}", self.player1.value, self.player2.value)}</h4>
+                <h4>{format!("New {} Game: {} Vs {} ({})", self.kind, self.player1.value, self.player2.value, self.variant().unwrap_or_default())}</h4>
                 <small disabled={!self.disabled}>{format!("(Disc Colors: {} - ", self.player1.value)} <b>{"Red"}</b> {format!("   and    {} - ", self.player2.value)} <b>{"Yellow)"}</b></small>
                 <br></br>
                 <CanvasModel: 
-                    canvas_id = "connect_human" 
+                    canvas_id = CANVAS_ID,
                     player1 = self.player1.value.clone(), 
                     player2=self.player2.value.clone(),
                     difficulty = Easy,
+                    resume = self.resume.clone(),
+                    variant = self.variant().unwrap_or_default(),
+                    pop_out = self.kind == GameKind::PopOut,
+                    power_up = self.kind == GameKind::PowerUp,
+                    five_in_a_row = self.kind == GameKind::FiveInARow,
                     game_done_cbk=&self.end_game_callback/>
             </div>
             </>
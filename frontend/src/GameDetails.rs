@@ -0,0 +1,150 @@
+use anyhow::Error;
+use stdweb::web::Date;
+use yew::format::{Json, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::{html, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew_router::prelude::*;
+
+use crate::Profile::player_link;
+use crate::Replay::ReplayModel;
+use crate::ScoreBoard::{encode, Game};
+
+// route prefix of the game pages, followed by the game's id
+pub const ROUTE_PREFIX: &str = "Games/";
+
+// the end of the id, enough to tell games in a table apart
+const SHORT_ID: usize = 8;
+
+// a link to the game's own page, labeled with the end of its id
+pub fn game_link(id: &str) -> Html {
+    if id.is_empty() {
+        return html! {};
+    }
+    let route = format!("/#/{}{}", ROUTE_PREFIX, encode(id));
+    let label = &id[id.len().saturating_sub(SHORT_ID)..];
+    html! {
+        <span title=id>
+            <RouterAnchor<String> route=route>{ label }</RouterAnchor<String>>
+        </span>
+    }
+}
+
+pub enum Msg {
+    GameReady(Game),
+    NotFound,
+    Ignore,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub id: String,
+}
+
+// one saved game, reachable by a link
+pub struct GameDetailsModel {
+    props: Props,
+    game: Option<Game>,
+    not_found: bool,
+    fetch_service: FetchService,
+    fetch_task: Option<FetchTask>,
+    link: ComponentLink<GameDetailsModel>,
+}
+
+impl GameDetailsModel {
+    fn fetch_game(&mut self) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Game, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(game) if meta.status.is_success() => Msg::GameReady(game),
+                    _ if meta.status.as_u16() == 404 => Msg::NotFound,
+                    _ => {
+                        error!("Failed to fetch game");
+                        Msg::Ignore
+                    }
+                }
+            });
+        let url = format!("/games/{}", encode(&self.props.id));
+        let request = Request::get(url).body(Nothing).unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn view_game(&self, game: &Game) -> Html {
+        html! {
+            <>
+            <table>
+                <tr><th>{"Game-ID"}</th><td>{ game.gameNumber.as_str() }</td></tr>
+                <tr><th>{"Game Type"}</th><td>{ game.type_label() }</td></tr>
+                <tr><th>{"Player1"}</th><td>{ player_link(&game.Player1Name) }</td></tr>
+                <tr><th>{"Player2"}</th><td>{ player_link(&game.Player2Name) }</td></tr>
+                <tr><th>{"Winner"}</th><td>{ player_link(&game.WinnerName) }</td></tr>
+                <tr><th>{"Difficulty"}</th><td>{ game.Difficulty.as_ref().map_or("-", String::as_str) }</td></tr>
+                <tr><th>{"When Played"}</th><td>{ &Date::from_time(game.GameDate as f64).to_string() }</td></tr>
+            </table>
+            <br></br>
+            {
+                if game.Moves.is_empty() {
+                    html! { <p>{"The moves of this game weren't recorded"}</p> }
+                } else {
+                    html! { <ReplayModel game=game.clone()/> }
+                }
+            }
+            </>
+        }
+    }
+}
+
+impl Component for GameDetailsModel {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut model = GameDetailsModel {
+            props,
+            game: None,
+            not_found: false,
+            fetch_service: FetchService::new(),
+            fetch_task: None,
+            link,
+        };
+        model.fetch_task = Some(model.fetch_game());
+        model
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::GameReady(game) => self.game = Some(game),
+            Msg::NotFound => self.not_found = true,
+            Msg::Ignore => return false,
+        }
+        self.fetch_task = None;
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            self.game = None;
+            self.not_found = false;
+            self.fetch_task = Some(self.fetch_game());
+            return true;
+        }
+        false
+    }
+
+    fn view(&self) -> Html {
+        let body = match &self.game {
+            Some(game) => self.view_game(game),
+            None if self.not_found => html! { <p>{"There is no game with that id"}</p> },
+            None => html! { <p>{"Loading..."}</p> },
+        };
+        html! {
+            <div class="w3-container" id="services" style="margin-top:75px">
+            <h5 class="w3-xxxlarge w3-text-red"><b>{"Game"}</b></h5>
+            <hr style="width:50px;border:5px solid red" class="w3-round"></hr>
+            { body }
+            </div>
+        }
+    }
+}
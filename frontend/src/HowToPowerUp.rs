@@ -0,0 +1,60 @@
+use yew::{prelude::*, virtual_dom::VNode, Properties};
+
+pub struct HowToPowerUpModel;
+
+impl Component for HowToPowerUpModel {
+    type Message = ();
+    type Properties = ();
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        HowToPowerUpModel
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        html! {
+            <div class="w3-container" id="services" style="margin-top:75px">
+                <h5 class="w3-xxxlarge w3-text-red"><b>{"How to Play Power-Up"}</b></h5>
+                <hr style="width:50px;border:5px solid red" class="w3-round"></hr>
+                <p>
+                    {"Power-Up is Connect 4 with special discs. Each player has one of every special disc and can play each of them once a game, in place of one of their ordinary discs. The first to line up four of their own discs still wins."}
+                </p>
+                <br></br>
+                <div><h5>{"The special discs:"}</h5></div>
+                <ul>
+
+                    <li>{"Anvil (A): crushes every disc in its column and lands at the bottom"}</li>
+
+                    <li>{"Bomb (B): lands on the top disc of its column and blows up with it, leaving the space empty"}</li>
+
+                    <li>{"Wall (W): a gray disc that counts for neither player and blocks lines through it"}</li>
+
+                    <li>{"x2: an ordinary disc of yours, after which you take another turn"}</li>
+
+                </ul>
+                <br></br>
+                <div><h5>{"To play Power-Up follow the following steps:"}</h5></div>
+                <ul>
+
+                    <li>{"Pick Power-Up as the rules when starting a Connect 4 game"}</li>
+
+                    <li>{"On your turn, click one of your special discs below the board to pick it, or click it again to go back to an ordinary disc"}</li>
+
+                    <li>{"Click on the desired column on the game board to drop the disc"}</li>
+
+                </ul>
+                <br></br>
+                <p>
+                    {"For the rules of ordinary discs see "}<a href="/#/HowToConnect4">{"How to Play Connect4"}</a>
+                </p>
+            </div>
+        }
+    }
+}
@@ -34,6 +34,8 @@ impl Component for HowToTootModel {
 
                     <li>{"Select the disc type T or O that you want to place"}</li>
 
+                    <li>{"Each player has six T's and six O's, the letters you have left are shown beside the board"}</li>
+
                     <li>{"Click on the desired column on the game board to place your disc"}</li>
 
                     <li>{"Try to spell TOOT or OTTO based on your winning combination, either horizontally or vertically or diagonally"}</li>
@@ -0,0 +1,179 @@
+use anyhow::Error;
+use serde::Deserialize;
+use yew::format::{Json, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::{components::Select, html, Component, ComponentLink, Html, ShouldRender};
+
+use engine::GameKind;
+
+use crate::Profile::player_link;
+
+// games the trend column looks back over
+const TREND_GAMES: usize = 10;
+
+pub enum Msg {
+    ChangeKind(GameKind),
+    FetchReady(Result<Vec<Rating>, Error>),
+    Ignore,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct RatingPoint {
+    GameDate: i64,
+    rating: i64,
+}
+
+// a row of GET /ratings
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct Rating {
+    name: String,
+    gameType: String,
+    rating: i64,
+    games: usize,
+    wins: usize,
+    draws: usize,
+    losses: usize,
+    history: Vec<RatingPoint>,
+    // the server's starting rating
+    initial: i64,
+}
+
+impl Rating {
+    // rating change over the last TREND_GAMES games, counting from the starting rating
+    // for players with fewer games
+    fn trend(&self) -> i64 {
+        let before = match self.history.len().checked_sub(TREND_GAMES + 1) {
+            Some(index) => self.history[index].rating,
+            None => self.initial,
+        };
+        self.rating - before
+    }
+}
+
+pub struct LeaderboardModel {
+    kind: GameKind,
+    fetch_service: FetchService,
+    fetch_task: Option<FetchTask>,
+    link: ComponentLink<LeaderboardModel>,
+    data: Option<Vec<Rating>>,
+}
+
+impl LeaderboardModel {
+    fn view_ratings(&self) -> Html {
+        let ratings = match &self.data {
+            Some(ratings) => ratings,
+            None => {
+                return html! {
+                    <tr><td colspan="6">{"Loading..."}</td></tr>
+                }
+            }
+        };
+        if ratings.is_empty() {
+            return html! {
+                <tr><td colspan="6">{"No rated games yet"}</td></tr>
+            };
+        }
+        html! {
+            { ratings.iter().enumerate().map(|(i, rating)| {
+                let trend = rating.trend();
+                html! {
+                    <tr>
+                    <td>{ i + 1 }</td>
+                    <td>{ player_link(&rating.name) }</td>
+                    <td>{ rating.rating }</td>
+                    <td>{ rating.games }</td>
+                    <td>{ format!("{} / {} / {}", rating.wins, rating.draws, rating.losses) }</td>
+                    <td>{ match trend {
+                        0 => String::from("-"),
+                        t if t > 0 => format!("\u{25b2} +{}", t),
+                        t => format!("\u{25bc} {}", t),
+                    } }</td>
+                    </tr>
+                }
+            }).collect::<Html>() }
+        }
+    }
+
+    fn fetch_ratings(&mut self) -> FetchTask {
+        let callback =
+            self.link
+                .callback(move |response: Response<Json<Result<Vec<Rating>, Error>>>| {
+                    let (meta, Json(data)) = response.into_parts();
+                    if meta.status.is_success() {
+                        Msg::FetchReady(data)
+                    } else {
+                        error!("Failed to fetch ratings");
+                        Msg::Ignore
+                    }
+                });
+        let url = format!("/ratings?gameType={}", self.kind.name());
+        let request = Request::get(url).body(Nothing).unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+}
+
+impl Component for LeaderboardModel {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut model = LeaderboardModel {
+            kind: GameKind::Connect4,
+            fetch_service: FetchService::new(),
+            fetch_task: None,
+            link,
+            data: None,
+        };
+        model.fetch_task = Some(model.fetch_ratings());
+        model
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::ChangeKind(kind) => {
+                self.kind = kind;
+                self.data = None;
+                self.fetch_task = Some(self.fetch_ratings());
+            }
+            Msg::FetchReady(response) => {
+                self.data = response.ok();
+                self.fetch_task = None;
+            }
+            Msg::Ignore => (),
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class="w3-container" id="services" style="margin-top:75px">
+            <h5 class="w3-xxxlarge w3-text-red"><b>{"Leaderboard"}</b></h5>
+            <hr style="width:50px;border:5px solid red" class="w3-round"></hr>
+            <Select<GameKind>
+                selected=Some(self.kind),
+                options=vec![GameKind::Connect4, GameKind::PopOut, GameKind::PowerUp, GameKind::FiveInARow, GameKind::TootOtto],
+                onchange=self.link.callback(|kind: GameKind| Msg::ChangeKind(kind)) />
+            <p><small>{"Elo ratings, the computer counts as 1000 on Easy, 1300 on Medium, 1600 on Hard and 2000 on Perfect"}</small></p>
+            <div id="game-stream">
+                <table>
+                    <tr>
+                        <th>{"Rank"}</th>
+                        <th>{"Player"}</th>
+                        <th>{"Rating"}</th>
+                        <th>{"Games"}</th>
+                        <th>{"Won / Drawn / Lost"}</th>
+                        <th>{ format!("Last {} Games", TREND_GAMES) }</th>
+                    </tr>
+                    { self.view_ratings() }
+                </table>
+            </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use serde::Deserialize;
+use serde_json::json;
+use yew::format::{Json, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::services::interval::IntervalTask;
+use yew::services::IntervalService;
+use yew::{components::Select, prelude::*, virtual_dom::VNode};
+
+use engine::{GameKind, GameState, Move, Player};
+
+use crate::canvas::CanvasModel;
+use crate::session::fetch_account;
+use crate::toot_canvas::TootCanvasModel;
+use crate::Connect4Computer::Difficulty::Easy;
+
+// a game room as the server reports it, Seat and Token only come back from create and join
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct Room {
+    roomCode: String,
+    gameType: String,
+    Player1Name: Option<String>,
+    Player2Name: Option<String>,
+    Moves: Vec<Move>,
+    #[serde(default)]
+    Seat: Option<u8>,
+    #[serde(default)]
+    Token: Option<String>,
+}
+
+impl Room {
+    // replays the room's moves to see whether the game has been decided
+    fn is_over(&self) -> bool {
+        let kind = match self.gameType.parse::<GameKind>() {
+            Ok(kind) => kind,
+            Err(_) => return false,
+        };
+        let mut state = GameState::new(kind);
+        for &mv in &self.Moves {
+            if state.apply_move(mv).is_err() {
+                return false;
+            }
+        }
+        state.is_over()
+    }
+}
+
+pub struct OnlineModel {
+    name: String,
+    // logged in player, who always plays under their account name
+    account: Option<String>,
+    kind: GameKind,
+    code: String,
+    letter: String,
+    room: Option<Room>,
+    seat: Option<Player>,
+    token: String,
+    // a move of ours the server hasn't answered yet
+    pending: bool,
+    status: String,
+    fetch_service: FetchService,
+    session_task: Option<FetchTask>,
+    join_task: Option<FetchTask>,
+    poll_task: Option<FetchTask>,
+    move_task: Option<FetchTask>,
+    interval_service: IntervalService,
+    poll: Option<IntervalTask>,
+    link: ComponentLink<OnlineModel>,
+}
+
+pub enum Msg {
+    SessionReady(Option<String>),
+    UpdateName(InputData),
+    ChangeKind(GameKind),
+    UpdateCode(InputData),
+    UpdateLetter(InputData),
+    Create,
+    Join,
+    Joined(Room),
+    JoinFailed(String),
+    Poll,
+    Polled(Room),
+    PlayMove(Move),
+    MoveSent(Option<Room>),
+    Leave,
+    Ignore,
+}
+
+impl OnlineModel {
+    fn join_room(&mut self, url: String, body: serde_json::Value) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Room, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(room) if meta.status.is_success() => Msg::Joined(room),
+                    _ => Msg::JoinFailed(match meta.status.as_u16() {
+                        403 => String::from("That name belongs to a registered player, log in to use it"),
+                        404 => String::from("There is no room with that code"),
+                        409 => String::from("That room already has two players"),
+                        422 => String::from("Computer and Draw can't be used as names"),
+                        _ => String::from("Couldn't reach the server"),
+                    }),
+                }
+            });
+        let request = Request::post(url)
+            .header("Content-Type", "application/json")
+            .body(Json(&body))
+            .unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn fetch_room(&mut self, code: &str) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Room, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(room) if meta.status.is_success() => Msg::Polled(room),
+                    _ => {
+                        error!("Failed to fetch room");
+                        Msg::Ignore
+                    }
+                }
+            });
+        let request = Request::get(format!("/rooms/{}", code))
+            .body(Nothing)
+            .unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn send_move(&mut self, code: &str, mv: Move) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Room, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(room) if meta.status.is_success() => Msg::MoveSent(Some(room)),
+                    _ => Msg::MoveSent(None),
+                }
+            });
+        let body = json!({ "Token": self.token, "Move": mv });
+        let request = Request::post(format!("/rooms/{}/moves", code))
+            .header("Content-Type", "application/json")
+            .body(Json(&body))
+            .unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    // takes the server's view of the room, polling stops once the game is decided
+    fn update_room(&mut self, room: Room) {
+        if room.is_over() {
+            self.poll = None;
+        }
+        self.room = Some(room);
+    }
+
+    fn view_setup(&self) -> Html {
+        html! {
+            <div>
+                <input
+                    type="text",
+                    placeholder="Your Name",
+                    value=&self.name,
+                    disabled={self.account.is_some()},
+                    oninput=self.link.callback(|e: InputData| Msg::UpdateName(e)),
+                />
+                <Select<GameKind>
+                    selected=Some(self.kind),
+                    options=vec![GameKind::Connect4, GameKind::PopOut, GameKind::PowerUp, GameKind::FiveInARow, GameKind::TootOtto],
+                    onchange=self.link.callback(|kind: GameKind| Msg::ChangeKind(kind)) />
+                <button onclick=self.link.callback(|_| Msg::Create) disabled={self.name.is_empty()}>
+                    { "Create Room" }
+                </button>
+                <br></br>
+                <input
+                    type="text",
+                    placeholder="Room Code",
+                    oninput=self.link.callback(|e: InputData| Msg::UpdateCode(e)),
+                />
+                <button onclick=self.link.callback(|_| Msg::Join) disabled={self.name.is_empty() || self.code.is_empty()}>
+                    { "Join Room" }
+                </button>
+                <p>{ &self.status }</p>
+            </div>
+        }
+    }
+
+    fn view_room(&self, room: &Room) -> Html {
+        let player1 = room.Player1Name.clone().unwrap_or_default();
+        let player2 = match &room.Player2Name {
+            Some(name) => name.clone(),
+            None => {
+                return html! {
+                    <div>
+                        <h4>{ format!("Room {}", room.roomCode) }</h4>
+                        <p>{ "Waiting for an opponent, send them the room code to join" }</p>
+                        <button onclick=self.link.callback(|_| Msg::Leave)>{ "Leave Room" }</button>
+                    </div>
+                }
+            }
+        };
+
+        let board = if room.gameType == GameKind::TootOtto.name() {
+            html! {
+                <>
+                {"Select a Disc Type:  "}
+                <input type="radio" id="online_T" value="T" checked={self.letter=="T"} oninput=self.link.callback(|e: InputData| Msg::UpdateLetter(e))/>
+                <label for="online_T">{"T"}</label>
+                <input type="radio" id="online_O" value="O" checked={self.letter=="O"} oninput=self.link.callback(|e: InputData| Msg::UpdateLetter(e))/>
+                <label for="online_O">{"O"}</label>
+                <TootCanvasModel:
+                    canvas_id = "online_toot"
+                    player1 = player1.clone(),
+                    player2 = player2.clone(),
+                    letter = self.letter.clone(),
+                    difficulty = Easy,
+                    game_done_cbk = self.link.callback(|_| Msg::Ignore),
+                    replay = Some(room.Moves.clone()),
+                    replay_step = room.Moves.len(),
+                    local_player = self.seat,
+                    move_cbk = Some(self.link.callback(Msg::PlayMove))/>
+                </>
+            }
+        } else {
+            html! {
+                <CanvasModel:
+                    canvas_id = "online_connect"
+                    player1 = player1.clone(),
+                    player2 = player2.clone(),
+                    difficulty = Easy,
+                    game_done_cbk = self.link.callback(|_| Msg::Ignore),
+                    replay = Some(room.Moves.clone()),
+                    replay_step = room.Moves.len(),
+                    local_player = self.seat,
+                    move_cbk = Some(self.link.callback(Msg::PlayMove)),
+                    pop_out = room.gameType == GameKind::PopOut.name(),
+                    power_up = room.gameType == GameKind::PowerUp.name(),
+                    five_in_a_row = room.gameType == GameKind::FiveInARow.name(),
+                    variant = room.gameType.parse::<GameKind>().map(|kind| kind.variant()).unwrap_or_default()/>
+            }
+        };
+
+        html! {
+            <div>
+                <h4>{ format!("Room {}: {} Vs {}", room.roomCode, player1, player2) }</h4>
+                <button onclick=self.link.callback(|_| Msg::Leave)>{ "Leave Room" }</button>
+                <p>{ &self.status }</p>
+                { board }
+            </div>
+        }
+    }
+}
+
+impl Component for OnlineModel {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut model = OnlineModel {
+            name: String::new(),
+            account: None,
+            kind: GameKind::Connect4,
+            code: String::new(),
+            letter: "T".to_string(),
+            room: None,
+            seat: None,
+            token: String::new(),
+            pending: false,
+            status: String::new(),
+            fetch_service: FetchService::new(),
+            session_task: None,
+            join_task: None,
+            poll_task: None,
+            move_task: None,
+            interval_service: IntervalService::new(),
+            poll: None,
+            link,
+        };
+        model.session_task = Some(fetch_account(&mut model.fetch_service, &model.link, Msg::SessionReady));
+        model
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::SessionReady(account) => {
+                if let Some(name) = &account {
+                    self.name = name.clone();
+                }
+                self.account = account;
+                self.session_task = None;
+            }
+            Msg::UpdateName(e) => self.name = e.value,
+            Msg::ChangeKind(kind) => self.kind = kind,
+            Msg::UpdateCode(e) => self.code = e.value.trim().to_uppercase(),
+            Msg::UpdateLetter(e) => self.letter = e.value,
+            Msg::Create => {
+                let body = json!({ "gameType": self.kind.name(), "PlayerName": self.name });
+                self.join_task = Some(self.join_room(String::from("/rooms"), body));
+            }
+            Msg::Join => {
+                let body = json!({ "PlayerName": self.name });
+                let url = format!("/rooms/{}/join", self.code);
+                self.join_task = Some(self.join_room(url, body));
+            }
+            Msg::Joined(room) => {
+                self.join_task = None;
+                self.seat = match room.Seat {
+                    Some(1) => Some(Player::One),
+                    Some(2) => Some(Player::Two),
+                    _ => None,
+                };
+                self.token = room.Token.clone().unwrap_or_default();
+                self.status = String::new();
+                self.room = Some(room);
+
+                let callback = self.link.callback(|_| Msg::Poll);
+                self.poll = Some(
+                    self.interval_service
+                        .spawn(Duration::from_millis(1000), callback),
+                );
+            }
+            Msg::JoinFailed(status) => {
+                self.join_task = None;
+                self.status = status;
+            }
+            Msg::Poll => {
+                // one request at a time, a slow server shouldn't pile them up
+                if self.poll_task.is_none() {
+                    if let Some(code) = self.room.as_ref().map(|room| room.roomCode.clone()) {
+                        self.poll_task = Some(self.fetch_room(&code));
+                    }
+                }
+                return false;
+            }
+            Msg::Polled(room) => {
+                self.poll_task = None;
+                // an answer from before our move reached the server would take it back
+                let local_moves = self.room.as_ref().map_or(0, |room| room.Moves.len());
+                if self.pending && room.Moves.len() < local_moves {
+                    return false;
+                }
+                self.update_room(room);
+            }
+            Msg::PlayMove(mv) => {
+                let code = match self.room.as_mut() {
+                    Some(room) => {
+                        // show the move straight away, the server answer confirms it
+                        room.Moves.push(mv);
+                        room.roomCode.clone()
+                    }
+                    None => return false,
+                };
+                self.pending = true;
+                self.status = String::new();
+                self.move_task = Some(self.send_move(&code, mv));
+            }
+            Msg::MoveSent(room) => {
+                self.move_task = None;
+                self.pending = false;
+                match room {
+                    Some(room) => self.update_room(room),
+                    None => {
+                        // the next poll puts the board back to the server's moves
+                        self.status = String::from("The server didn't accept that move");
+                    }
+                }
+            }
+            Msg::Leave => {
+                self.room = None;
+                self.seat = None;
+                self.poll = None;
+                self.poll_task = None;
+                self.status = String::new();
+            }
+            Msg::Ignore => return false,
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> VNode {
+        html! {
+            <>
+            <div class="w3-container" id="services" style="margin-top:75px">
+            <h5 class="w3-xxxlarge w3-text-red"><b>{"Play Online"}</b></h5>
+            <hr style="width:50px;border:5px solid red" class="w3-round"></hr>
+            </div>
+            {
+                match &self.room {
+                    Some(room) => self.view_room(room),
+                    None => self.view_setup(),
+                }
+            }
+            </>
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use anyhow::Error;
+use serde::Deserialize;
+use stdweb::web::Date;
+use yew::format::{Json, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::{html, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew_router::prelude::*;
+
+use crate::ScoreBoard::{encode, Game, Page};
+
+// games listed per page
+const PAGE_SIZE: usize = 20;
+
+// route prefix of the profile pages, followed by the encoded player name
+pub const ROUTE_PREFIX: &str = "Players/";
+
+// the player's name linking to their profile, names that aren't players stay plain text
+pub fn player_link(name: &str) -> Html {
+    if name == "Computer" || name == "Draw" || name.is_empty() {
+        return html! { { name } };
+    }
+    let route = format!("/#/{}{}", ROUTE_PREFIX, encode(name));
+    html! {
+        <RouterAnchor<String> route=route>{ name }</RouterAnchor<String>>
+    }
+}
+
+pub enum Msg {
+    ProfileReady(Profile),
+    NotFound,
+    GamesReady(Page),
+    PreviousPage,
+    NextPage,
+    Ignore,
+}
+
+// wins, losses and draws from the player's side
+#[derive(Deserialize, Debug)]
+pub struct Record {
+    games: usize,
+    wins: usize,
+    losses: usize,
+    draws: usize,
+}
+
+impl Record {
+    fn win_rate(&self) -> String {
+        if self.games == 0 {
+            return String::from("-");
+        }
+        format!("{}%", self.wins * 100 / self.games)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct GameTypeRecord {
+    gameType: String,
+    #[serde(flatten)]
+    record: Record,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct DifficultyRecord {
+    gameType: String,
+    #[serde(default)]
+    difficulty: Option<String>,
+    #[serde(flatten)]
+    record: Record,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OpponentRecord {
+    opponent: String,
+    #[serde(flatten)]
+    record: Record,
+}
+
+// GET /players/<name>
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct Profile {
+    name: String,
+    #[serde(flatten)]
+    record: Record,
+    byGameType: Vec<GameTypeRecord>,
+    vsComputer: Vec<DifficultyRecord>,
+    headToHead: Vec<OpponentRecord>,
+    longestWinStreak: usize,
+    longestLossStreak: usize,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub name: String,
+}
+
+// one player's record and games
+pub struct ProfileModel {
+    props: Props,
+    offset: usize,
+    profile: Option<Profile>,
+    not_found: bool,
+    games: Option<Page>,
+    fetch_service: FetchService,
+    profile_task: Option<FetchTask>,
+    games_task: Option<FetchTask>,
+    link: ComponentLink<ProfileModel>,
+}
+
+fn record_cells(record: &Record) -> Html {
+    html! {
+        <>
+        <td>{ record.games }</td>
+        <td>{ record.wins }</td>
+        <td>{ record.losses }</td>
+        <td>{ record.draws }</td>
+        <td>{ record.win_rate() }</td>
+        </>
+    }
+}
+
+fn record_headings() -> Html {
+    html! {
+        <>
+        <th>{"Games"}</th>
+        <th>{"Won"}</th>
+        <th>{"Lost"}</th>
+        <th>{"Drawn"}</th>
+        <th>{"Win Rate"}</th>
+        </>
+    }
+}
+
+impl ProfileModel {
+    fn fetch_profile(&mut self) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Profile, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(profile) if meta.status.is_success() => Msg::ProfileReady(profile),
+                    _ if meta.status.as_u16() == 404 => Msg::NotFound,
+                    _ => {
+                        error!("Failed to fetch profile");
+                        Msg::Ignore
+                    }
+                }
+            });
+        let url = format!("/players/{}", encode(&self.props.name));
+        let request = Request::get(url).body(Nothing).unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn fetch_games(&mut self) -> FetchTask {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<Page, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(page) if meta.status.is_success() => Msg::GamesReady(page),
+                    _ => {
+                        error!("Failed to fetch games");
+                        Msg::Ignore
+                    }
+                }
+            });
+        let url = format!(
+            "/games?player={}&limit={}&offset={}",
+            encode(&self.props.name),
+            PAGE_SIZE,
+            self.offset
+        );
+        let request = Request::get(url).body(Nothing).unwrap();
+        self.fetch_service.fetch(request, callback).unwrap()
+    }
+
+    fn reload(&mut self) {
+        self.offset = 0;
+        self.profile = None;
+        self.not_found = false;
+        self.games = None;
+        self.profile_task = Some(self.fetch_profile());
+        self.games_task = Some(self.fetch_games());
+    }
+
+    fn view_profile(&self, profile: &Profile) -> Html {
+        html! {
+            <>
+            <p>
+                { format!(
+                    "{} games: {} won, {} lost, {} drawn ({} won). Longest winning streak {}, longest losing streak {}.",
+                    profile.record.games,
+                    profile.record.wins,
+                    profile.record.losses,
+                    profile.record.draws,
+                    profile.record.win_rate(),
+                    profile.longestWinStreak,
+                    profile.longestLossStreak,
+                ) }
+            </p>
+            <div><h4>{"By Game Type"}</h4></div>
+            <table>
+                <tr><th>{"Game Type"}</th>{ record_headings() }</tr>
+                { profile.byGameType.iter().map(|row| html! {
+                    <tr><td>{ row.gameType.as_str() }</td>{ record_cells(&row.record) }</tr>
+                }).collect::<Html>() }
+            </table>
+            <br></br>
+            <div><h4>{"Against the Computer"}</h4></div>
+            <table>
+                <tr><th>{"Game Type"}</th><th>{"Difficulty"}</th>{ record_headings() }</tr>
+                { profile.vsComputer.iter().map(|row| html! {
+                    <tr>
+                        <td>{ row.gameType.as_str() }</td>
+                        <td>{ row.difficulty.as_ref().map_or("Unknown", String::as_str) }</td>
+                        { record_cells(&row.record) }
+                    </tr>
+                }).collect::<Html>() }
+            </table>
+            <br></br>
+            <div><h4>{"Head to Head"}</h4></div>
+            <table>
+                <tr><th>{"Opponent"}</th>{ record_headings() }</tr>
+                { profile.headToHead.iter().map(|row| html! {
+                    <tr><td>{ player_link(&row.opponent) }</td>{ record_cells(&row.record) }</tr>
+                }).collect::<Html>() }
+            </table>
+            <br></br>
+            </>
+        }
+    }
+
+    fn view_games(&self) -> Html {
+        let page = match &self.games {
+            Some(page) => page,
+            None => return html! {},
+        };
+        let name = &self.props.name;
+        let last = std::cmp::min(page.offset + page.items.len(), page.total);
+        let first = if last == 0 { 0 } else { page.offset + 1 };
+        html! {
+            <>
+            <div><h4>{"Games"}</h4></div>
+            <div id="game-stream">
+            <table>
+                <tr>
+                    <th>{"Game Type"}</th>
+                    <th>{"Opponent"}</th>
+                    <th>{"Result"}</th>
+                    <th>{"When Played"}</th>
+                </tr>
+                { page.items.iter().map(|game| {
+                    let opponent = if game.Player1Name == *name { &game.Player2Name } else { &game.Player1Name };
+                    let result = if game.WinnerName == *name {
+                        "Won"
+                    } else if game.WinnerName == "Draw" {
+                        "Drawn"
+                    } else {
+                        "Lost"
+                    };
+                    html! {
+                        <tr>
+                        <td>{ game.type_label() }</td>
+                        <td>{ player_link(opponent) }</td>
+                        <td>{ result }</td>
+                        <td>{ &Date::from_time(game.GameDate as f64).to_string() }</td>
+                        </tr>
+                    }
+                }).collect::<Html>() }
+            </table>
+            </div>
+            <button onclick=self.link.callback(|_| Msg::PreviousPage) disabled={page.offset == 0}>{"Previous"}</button>
+            <span>{ format!("  Games {}-{} of {}  ", first, last, page.total) }</span>
+            <button onclick=self.link.callback(|_| Msg::NextPage) disabled={last >= page.total}>{"Next"}</button>
+            </>
+        }
+    }
+}
+
+impl Component for ProfileModel {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut model = ProfileModel {
+            props,
+            offset: 0,
+            profile: None,
+            not_found: false,
+            games: None,
+            fetch_service: FetchService::new(),
+            profile_task: None,
+            games_task: None,
+            link,
+        };
+        model.reload();
+        model
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::ProfileReady(profile) => {
+                self.profile = Some(profile);
+                self.profile_task = None;
+            }
+            Msg::NotFound => {
+                self.not_found = true;
+                self.profile_task = None;
+            }
+            Msg::GamesReady(page) => {
+                self.games = Some(page);
+                self.games_task = None;
+            }
+            Msg::PreviousPage => {
+                self.offset = self.offset.saturating_sub(PAGE_SIZE);
+                self.games_task = Some(self.fetch_games());
+            }
+            Msg::NextPage => {
+                self.offset += PAGE_SIZE;
+                self.games_task = Some(self.fetch_games());
+            }
+            Msg::Ignore => return false,
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            self.reload();
+            return true;
+        }
+        false
+    }
+
+    fn view(&self) -> Html {
+        let body = match &self.profile {
+            Some(profile) => html! {
+                <>
+                { self.view_profile(profile) }
+                { self.view_games() }
+                </>
+            },
+            None if self.not_found => html! { <p>{"No games recorded for this player"}</p> },
+            None => html! { <p>{"Loading..."}</p> },
+        };
+        html! {
+            <div class="w3-container" id="services" style="margin-top:75px">
+            <h5 class="w3-xxxlarge w3-text-red"><b>{ self.props.name.as_str() }</b></h5>
+            <hr style="width:50px;border:5px solid red" class="w3-round"></hr>
+            { body }
+            </div>
+        }
+    }
+}
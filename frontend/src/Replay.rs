@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use yew::services::interval::IntervalTask;
+use yew::services::IntervalService;
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+
+use engine::GameKind;
+
+use crate::canvas::CanvasModel;
+use crate::toot_canvas::TootCanvasModel;
+use crate::Connect4Computer::Difficulty::Easy;
+use crate::ScoreBoard::Game;
+
+// steps through the recorded moves of a saved game
+pub struct ReplayModel {
+    props: Props,
+    step: usize,
+    interval_service: IntervalService,
+    autoplay: Option<IntervalTask>,
+    game_done_cbk: Callback<i64>,
+    link: ComponentLink<ReplayModel>,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub game: Game,
+}
+
+pub enum Msg {
+    Start,
+    Back,
+    Forward,
+    ToggleAutoplay,
+    Tick,
+    Ignore,
+}
+
+impl ReplayModel {
+    fn view_board(&self) -> Html {
+        let game = &self.props.game;
+        if game.gameType == "TOOT-OTTO" {
+            html! {
+                <TootCanvasModel:
+                    canvas_id = "replay_toot"
+                    player1 = game.Player1Name.clone(),
+                    player2 = game.Player2Name.clone(),
+                    letter = "T",
+                    difficulty = Easy,
+                    game_done_cbk = &self.game_done_cbk,
+                    replay = Some(game.Moves.clone()),
+                    replay_step = self.step/>
+            }
+        } else {
+            // records of a kind's own board leave out the variant
+            let kind = game.gameType.parse::<GameKind>().unwrap_or(GameKind::Connect4);
+            html! {
+                <CanvasModel:
+                    canvas_id = "replay_connect"
+                    player1 = game.Player1Name.clone(),
+                    player2 = game.Player2Name.clone(),
+                    difficulty = Easy,
+                    game_done_cbk = &self.game_done_cbk,
+                    replay = Some(game.Moves.clone()),
+                    replay_step = self.step,
+                    variant = game.Variant.unwrap_or_else(|| kind.variant()),
+                    pop_out = game.gameType == GameKind::PopOut.name(),
+                    power_up = game.gameType == GameKind::PowerUp.name(),
+                    five_in_a_row = game.gameType == GameKind::FiveInARow.name()/>
+            }
+        }
+    }
+}
+
+impl Component for ReplayModel {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        ReplayModel {
+            props,
+            step: 0,
+            interval_service: IntervalService::new(),
+            autoplay: None,
+            game_done_cbk: link.callback(|_| Msg::Ignore),
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let moves = self.props.game.Moves.len();
+        match msg {
+            Msg::Start => self.step = 0,
+            Msg::Back => self.step = self.step.saturating_sub(1),
+            Msg::Forward => {
+                if self.step < moves {
+                    self.step += 1;
+                }
+            }
+            Msg::ToggleAutoplay => {
+                if self.autoplay.is_some() {
+                    self.autoplay = None;
+                } else {
+                    // start over when the replay already reached the end
+                    if self.step == moves {
+                        self.step = 0;
+                    }
+                    let callback = self.link.callback(|_| Msg::Tick);
+                    let task = self
+                        .interval_service
+                        .spawn(Duration::from_millis(800), callback);
+                    self.autoplay = Some(task);
+                }
+            }
+            Msg::Tick => {
+                if self.step < moves {
+                    self.step += 1;
+                } else {
+                    self.autoplay = None;
+                }
+            }
+            Msg::Ignore => return false,
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            self.props = props;
+            self.step = 0;
+            self.autoplay = None;
+            return true;
+        }
+        false
+    }
+
+    fn view(&self) -> Html {
+        let game = &self.props.game;
+        let moves = game.Moves.len();
+        html! {
+            <div>
+                <h4>{format!("Replay: {} Vs {} ({})", game.Player1Name, game.Player2Name, game.type_label())}</h4>
+                <button onclick=self.link.callback(|_| Msg::Start) disabled={self.step == 0}>{"<< Start"}</button>
+                <button onclick=self.link.callback(|_| Msg::Back) disabled={self.step == 0}>{"< Back"}</button>
+                <button onclick=self.link.callback(|_| Msg::Forward) disabled={self.step == moves}>{"Forward >"}</button>
+                <button onclick=self.link.callback(|_| Msg::ToggleAutoplay)>
+                    { if self.autoplay.is_some() { "Pause" } else { "Autoplay" } }
+                </button>
+                <span>{format!("  Move {} of {}", self.step, moves)}</span>
+                { self.view_board() }
+            </div>
+        }
+    }
+}
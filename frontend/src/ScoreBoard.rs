@@ -1,16 +1,37 @@
 use anyhow::Error;
+use engine::{Inventory, Move, Variant};
 use serde::{Deserialize, Serialize};
+use stdweb::unstable::TryInto;
 use stdweb::web::Date;
 use yew::format::{Json, Nothing};
 use yew::services::fetch::{FetchService, FetchTask, Request, Response};
-use yew::{html, Component, ComponentLink, Html, ShouldRender};
+use yew::{html, ChangeData, Component, ComponentLink, Html, InputData, ShouldRender};
+
+use crate::GameDetails::game_link;
+use crate::Profile::player_link;
+use crate::Replay::ReplayModel;
+
+// games shown per page
+const PAGE_SIZE: usize = 20;
 
 pub enum Msg {
-    FetchReady(Result<Vec<Game>, Error>),
+    FetchReady(Result<Page, Error>),
+    UpdateGameType(ChangeData),
+    UpdatePlayer(InputData),
+    UpdateWinner(InputData),
+    UpdateFrom(InputData),
+    UpdateTo(InputData),
+    UpdateSort(ChangeData),
+    ToggleOrder,
+    ApplyFilters,
+    PreviousPage,
+    NextPage,
+    Replay(usize),
+    CloseReplay,
     Ignore,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[allow(non_snake_case)]
 pub struct Game {
     pub gameNumber: String,
@@ -19,44 +40,249 @@ pub struct Game {
     pub Player2Name: String,
     pub WinnerName: String,
     pub GameDate: u64,
+    // letters each player had left, TOOT-OTTO only
+    #[serde(default)]
+    pub Player1Inventory: Option<Inventory>,
+    #[serde(default)]
+    pub Player2Inventory: Option<Inventory>,
+    // every move in the order played, empty for games saved before moves were recorded
+    #[serde(default)]
+    pub Moves: Vec<Move>,
+    // the players took moves back during the game
+    #[serde(default)]
+    pub UsedUndo: bool,
+    // the computer's difficulty, only for games against the Computer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub Difficulty: Option<String>,
+    // this browser's id for a game it posted, so a resent game is only stored once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ClientId: Option<String>,
+    // board size and line length of Connect-N games, left out for the standard board
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub Variant: Option<Variant>,
+}
+
+impl Game {
+    // the game type, with the board for Connect-N games
+    pub fn type_label(&self) -> String {
+        match &self.Variant {
+            Some(variant) => format!("{} ({})", self.gameType, variant),
+            None => self.gameType.clone(),
+        }
+    }
+}
+
+// body of a request the server rejected
+#[derive(Deserialize, Debug)]
+pub struct ApiError {
+    pub error: String,
+    pub code: String,
+}
+
+// the server's reason for rejecting a request, from the response body
+pub fn error_message(body: Result<String, Error>) -> String {
+    body.ok()
+        .and_then(|body| serde_json::from_str::<ApiError>(&body).ok())
+        .map_or_else(|| String::from("the server couldn't be reached"), |error| error.error)
+}
+
+// one page of GET /games and how many games match altogether
+#[derive(Deserialize, Debug)]
+pub struct Page {
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub items: Vec<Game>,
+}
+
+// the filter controls, empty strings are left out of the query
+#[derive(Default)]
+struct Filters {
+    game_type: String,
+    player: String,
+    winner: String,
+    from: String,
+    to: String,
+    sort: String,
+    ascending: bool,
+}
+
+impl Filters {
+    fn query(&self, offset: usize) -> String {
+        let mut query = format!("limit={}&offset={}", PAGE_SIZE, offset);
+        for &(key, value) in &[
+            ("gameType", &self.game_type),
+            ("player", &self.player),
+            ("winner", &self.winner),
+            ("sort", &self.sort),
+        ] {
+            if !value.is_empty() {
+                query += &format!("&{}={}", key, encode(value));
+            }
+        }
+        if let Some(from) = date_millis(&self.from) {
+            query += &format!("&from={}", from);
+        }
+        // up to the end of the chosen day
+        if let Some(to) = date_millis(&self.to) {
+            query += &format!("&to={}", to + 24 * 60 * 60 * 1000 - 1);
+        }
+        if self.ascending {
+            query += "&order=asc";
+        }
+        query
+    }
+}
+
+pub fn encode(value: &str) -> String {
+    let encoded = js! { return encodeURIComponent(@{value}); };
+    encoded.try_into().unwrap()
+}
+
+pub fn decode(value: &str) -> String {
+    let decoded = js! { return decodeURIComponent(@{value}); };
+    decoded.try_into().unwrap_or_else(|_| value.to_string())
+}
+
+// milliseconds at the start of a yyyy-mm-dd date input, None when it's empty
+fn date_millis(value: &str) -> Option<i64> {
+    if value.is_empty() {
+        return None;
+    }
+    let millis = js! { return Date.parse(@{value}); };
+    let millis: f64 = millis.try_into().ok()?;
+    if millis.is_nan() {
+        None
+    } else {
+        Some(millis as i64)
+    }
+}
+
+fn select_value(data: ChangeData) -> String {
+    match data {
+        ChangeData::Select(select) => select.value().unwrap_or_default(),
+        ChangeData::Value(value) => value,
+        ChangeData::Files(_) => String::new(),
+    }
 }
 
 pub struct ScoreBoardModel {
     fetch_service: FetchService,
     fetch_task: Option<FetchTask>,
     link: ComponentLink<ScoreBoardModel>,
-    data: Option<Vec<Game>>,
+    filters: Filters,
+    offset: usize,
+    data: Option<Page>,
+    replay: Option<usize>,
 }
 
 impl ScoreBoardModel {
     fn view_data(&self) -> Html {
-        if let Some(ref games) = self.data {
+        if let Some(ref page) = self.data {
+            if page.items.is_empty() {
+                return html! {
+                    <tr><td colspan="7">{"No games found"}</td></tr>
+                };
+            }
             html! {
-                { games.iter().enumerate().map(|(i, game)| {
-                    let date = Date::from_time(game.GameDate as f64);
+                { page.items.iter().enumerate().map(|(i, game)| {
                     html! {
                         <tr>
-                        <td>{ i + 1 }</td>
-                        <td>{ game.gameType.as_str() }</td>
-                        <td>{ game.Player1Name.as_str() }</td>
-                        <td>{ game.Player2Name.as_str() }</td>
-                        <td>{ game.WinnerName.as_str() }</td>
+                        <td>{ game_link(&game.gameNumber) }</td>
+                        <td>{ game.type_label() }</td>
+                        <td>{ player_link(&game.Player1Name) }</td>
+                        <td>{ player_link(&game.Player2Name) }</td>
+                        <td>{ player_link(&game.WinnerName) }</td>
                         <td>{ &Date::from_time(game.GameDate as f64).to_string() }</td>
+                        <td>{ self.view_replay_button(i, game) }</td>
                         </tr>
                     }
                 }).collect::<Html>() }
             }
         } else {
             html! {
-                <tr><td colspan="6">{"Loading..."}</td></tr>
+                <tr><td colspan="7">{"Loading..."}</td></tr>
             }
         }
     }
 
+    fn view_replay_button(&self, index: usize, game: &Game) -> Html {
+        if game.Moves.is_empty() {
+            return html! {};
+        }
+        html! {
+            <button onclick=self.link.callback(move |_| Msg::Replay(index))>{"Replay"}</button>
+        }
+    }
+
+    fn view_replay(&self) -> Html {
+        let game = match (self.replay, &self.data) {
+            (Some(index), Some(page)) => &page.items[index],
+            _ => return html! {},
+        };
+        html! {
+            <div>
+                <ReplayModel game=game.clone()/>
+                <button onclick=self.link.callback(|_| Msg::CloseReplay)>{"Close Replay"}</button>
+                <br></br>
+            </div>
+        }
+    }
+
+    fn view_filters(&self) -> Html {
+        html! {
+            <div>
+                <select onchange=self.link.callback(|e: ChangeData| Msg::UpdateGameType(e))>
+                    <option value="" selected={self.filters.game_type.is_empty()}>{"All Games"}</option>
+                    <option value="Connect-4">{"Connect-4"}</option>
+                    <option value="Pop-Out">{"Pop-Out"}</option>
+                    <option value="Power-Up">{"Power-Up"}</option>
+                    <option value="5-in-a-Row">{"5-in-a-Row"}</option>
+                    <option value="TOOT-OTTO">{"TOOT-OTTO"}</option>
+                </select>
+                <input type="text" placeholder="Player" oninput=self.link.callback(|e: InputData| Msg::UpdatePlayer(e))/>
+                <input type="text" placeholder="Winner" oninput=self.link.callback(|e: InputData| Msg::UpdateWinner(e))/>
+                {" From "}
+                <input type="date" oninput=self.link.callback(|e: InputData| Msg::UpdateFrom(e))/>
+                {" To "}
+                <input type="date" oninput=self.link.callback(|e: InputData| Msg::UpdateTo(e))/>
+                <br></br>
+                {"Sort by "}
+                <select onchange=self.link.callback(|e: ChangeData| Msg::UpdateSort(e))>
+                    <option value="date">{"When Played"}</option>
+                    <option value="gameType">{"Game Type"}</option>
+                    <option value="player1">{"Player1"}</option>
+                    <option value="player2">{"Player2"}</option>
+                    <option value="winner">{"Winner"}</option>
+                </select>
+                <button onclick=self.link.callback(|_| Msg::ToggleOrder)>
+                    { if self.filters.ascending { "Ascending" } else { "Descending" } }
+                </button>
+                <button onclick=self.link.callback(|_| Msg::ApplyFilters)>{"Apply Filters"}</button>
+            </div>
+        }
+    }
+
+    fn view_pagination(&self) -> Html {
+        let page = match &self.data {
+            Some(page) => page,
+            None => return html! {},
+        };
+        let last = std::cmp::min(page.offset + page.items.len(), page.total);
+        let first = if last == 0 { 0 } else { page.offset + 1 };
+        html! {
+            <div>
+                <button onclick=self.link.callback(|_| Msg::PreviousPage) disabled={page.offset == 0}>{"Previous"}</button>
+                <span>{ format!("  Games {}-{} of {}  ", first, last, page.total) }</span>
+                <button onclick=self.link.callback(|_| Msg::NextPage) disabled={last >= page.total}>{"Next"}</button>
+            </div>
+        }
+    }
+
     fn fetch_games(&mut self) -> FetchTask {
         let callback =
             self.link
-                .callback(move |response: Response<Json<Result<Vec<Game>, Error>>>| {
+                .callback(move |response: Response<Json<Result<Page, Error>>>| {
                     let (meta, Json(data)) = response.into_parts();
                     if meta.status.is_success() {
                         Msg::FetchReady(data)
@@ -65,7 +291,8 @@ impl ScoreBoardModel {
                         Msg::Ignore
                     }
                 });
-        let request = Request::get("/games").body(Nothing).unwrap();
+        let url = format!("/games?{}", self.filters.query(self.offset));
+        let request = Request::get(url).body(Nothing).unwrap();
         self.fetch_service.fetch(request, callback).unwrap()
     }
 }
@@ -79,7 +306,10 @@ impl Component for ScoreBoardModel {
             fetch_service: FetchService::new(),
             fetch_task: None,
             link,
+            filters: Filters::default(),
+            offset: 0,
             data: None,
+            replay: None,
         };
         model.fetch_task = Some(model.fetch_games());
         model
@@ -88,9 +318,33 @@ impl Component for ScoreBoardModel {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::FetchReady(response) => {
-                self.data = response.map(|data| data).ok();
+                self.data = response.ok();
                 self.fetch_task = None;
             }
+            Msg::UpdateGameType(data) => self.filters.game_type = select_value(data),
+            Msg::UpdatePlayer(e) => self.filters.player = e.value.trim().to_string(),
+            Msg::UpdateWinner(e) => self.filters.winner = e.value.trim().to_string(),
+            Msg::UpdateFrom(e) => self.filters.from = e.value,
+            Msg::UpdateTo(e) => self.filters.to = e.value,
+            Msg::UpdateSort(data) => self.filters.sort = select_value(data),
+            Msg::ToggleOrder => self.filters.ascending = !self.filters.ascending,
+            Msg::ApplyFilters => {
+                self.offset = 0;
+                self.replay = None;
+                self.fetch_task = Some(self.fetch_games());
+            }
+            Msg::PreviousPage => {
+                self.offset = self.offset.saturating_sub(PAGE_SIZE);
+                self.replay = None;
+                self.fetch_task = Some(self.fetch_games());
+            }
+            Msg::NextPage => {
+                self.offset += PAGE_SIZE;
+                self.replay = None;
+                self.fetch_task = Some(self.fetch_games());
+            }
+            Msg::Replay(index) => self.replay = Some(index),
+            Msg::CloseReplay => self.replay = None,
             Msg::Ignore => (),
         }
         true
@@ -105,6 +359,8 @@ impl Component for ScoreBoardModel {
             <div class="w3-container" id="services" style="margin-top:75px">
             <h5 class="w3-xxxlarge w3-text-red"><b>{"Game History"}</b></h5>
             <hr style="width:50px;border:5px solid red" class="w3-round"> </hr>
+            { self.view_replay() }
+            { self.view_filters() }
             <div id="game-stream">
             <table>
                 <tr>
@@ -114,10 +370,12 @@ impl Component for ScoreBoardModel {
                     <th>{"Player2"}</th>
                     <th>{"Winner"}</th>
                     <th>{"When Played"}</th>
+                    <th></th>
                 </tr>
                 { self.view_data() }
             </table>
             </div>
+            { self.view_pagination() }
             </div>
         }
     }
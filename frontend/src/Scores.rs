@@ -1,35 +1,121 @@
-use std::collections::HashMap;
-
 use anyhow::Error;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use stdweb::web::Date;
 use yew::format::{Json, Nothing};
 use yew::services::fetch::{FetchService, FetchTask, Request, Response};
 use yew::{html, Component, ComponentLink, Html, ShouldRender};
 
-use crate::ScoreBoard::Game;
+use crate::GameDetails::game_link;
+use crate::Profile::player_link;
+use crate::ScoreBoard::Page;
+
+// most recent computer wins listed
+const COMPUTER_WINS_SHOWN: usize = 100;
 
 pub enum Msg {
-    FetchReady(Result<Vec<Game>, Error>),
+    SummaryReady(Summary),
+    ComputerReady(Vec<ComputerRecord>),
+    ComputerWinsReady(Page),
+    PlayersReady(Vec<PlayerWins>),
     Ignore,
 }
 
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct GameTypeSummary {
+    gameType: String,
+    games: usize,
+    computerGames: usize,
+    computerWins: usize,
+    draws: usize,
+}
+
+// GET /stats/summary
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct Summary {
+    games: usize,
+    computerGames: usize,
+    computerWins: usize,
+    draws: usize,
+    byGameType: Vec<GameTypeSummary>,
+}
+
+// a row of GET /stats/computer
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct ComputerRecord {
+    gameType: String,
+    #[serde(default)]
+    difficulty: Option<String>,
+    games: usize,
+    wins: usize,
+    losses: usize,
+    draws: usize,
+}
+
+// a row of GET /stats/players
+#[derive(Deserialize, Debug)]
+pub struct PlayerWins {
+    name: String,
+    wins: usize,
+}
+
 pub struct ScoresModel {
     fetch_service: FetchService,
-    fetch_task: Option<FetchTask>,
+    fetch_tasks: Vec<FetchTask>,
     link: ComponentLink<ScoresModel>,
-    data: Option<Vec<Game>>,
+    summary: Option<Summary>,
+    computer: Option<Vec<ComputerRecord>>,
+    computer_wins: Option<Page>,
+    players: Option<Vec<PlayerWins>>,
 }
 
 impl ScoresModel {
     fn view_total_games(&self) -> Html {
-        if let Some(ref games) = self.data {
+        if let Some(ref summary) = self.summary {
             html! {
+                <>
                 <tr>
-                    <td>{ games.len() }</td>
-                    <td>{ games.iter().filter(|game| game.Player2Name == "Computer").count() }</td>
-                    <td>{ games.iter().filter(|game| game.WinnerName == "Computer").count() }</td>
+                    <td>{ "All Games" }</td>
+                    <td>{ summary.games }</td>
+                    <td>{ summary.computerGames }</td>
+                    <td>{ summary.computerWins }</td>
+                    <td>{ summary.draws }</td>
                 </tr>
+                { summary.byGameType.iter().map(|by_type| {
+                    html! {
+                        <tr>
+                        <td>{ by_type.gameType.as_str() }</td>
+                        <td>{ by_type.games }</td>
+                        <td>{ by_type.computerGames }</td>
+                        <td>{ by_type.computerWins }</td>
+                        <td>{ by_type.draws }</td>
+                        </tr>
+                    }
+                }).collect::<Html>() }
+                </>
+            }
+        } else {
+            html! {}
+        }
+    }
+
+    fn view_difficulties(&self) -> Html {
+        if let Some(ref records) = self.computer {
+            html! {
+                { records.iter().map(|record| {
+                    html! {
+                        <tr>
+                        <td>{ record.gameType.as_str() }</td>
+                        <td>{ record.difficulty.as_ref().map_or("Unknown", String::as_str) }</td>
+                        <td>{ record.games }</td>
+                        <td>{ record.wins }</td>
+                        <td>{ record.losses }</td>
+                        <td>{ record.draws }</td>
+                        </tr>
+                    }
+                }).collect::<Html>() }
             }
         } else {
             html! {}
@@ -37,16 +123,16 @@ impl ScoresModel {
     }
 
     fn view_computer_wins(&self) -> Html {
-        if let Some(ref games) = self.data {
+        if let Some(ref page) = self.computer_wins {
             html! {
-                { games.iter().filter(|game| game.WinnerName == "Computer").enumerate().map(|(i, game)| {
-                    let date = Date::from_time(game.GameDate as f64);
+                { page.items.iter().enumerate().map(|(i, game)| {
                     html! {
                         <tr>
                         <td>{ i + 1 }</td>
-                        <td>{ game.gameType.as_str() }</td>
-                        <td>{ game.WinnerName.as_str() }</td>
-                        <td>{ game.Player1Name.as_str() }</td>
+                        <td>{ game_link(&game.gameNumber) }</td>
+                        <td>{ game.type_label() }</td>
+                        <td>{ player_link(&game.WinnerName) }</td>
+                        <td>{ player_link(&game.Player1Name) }</td>
                         <td>{ &Date::from_time(game.GameDate as f64).to_string() }</td>
                         </tr>
                     }
@@ -58,25 +144,14 @@ impl ScoresModel {
     }
 
     fn view_total_wins(&self) -> Html {
-        if let Some(ref games) = self.data {
-            // aggregate total wins for each player
-            let mut counts = HashMap::new();
-            for game in games.iter().filter(|game| game.WinnerName != "Draw") {
-                *counts.entry(game.WinnerName.as_str()).or_insert(0) += 1;
-            }
-
-            // sort by win count
-            let mut counts: Vec<_> = counts.iter().collect();
-            counts.sort_by(|a, b| a.1.partial_cmp(b.1).unwrap());
-
+        if let Some(ref players) = self.players {
             html! {
-
-                { counts.iter().enumerate().map(|(i, (player, count))| {
+                { players.iter().enumerate().map(|(i, player)| {
                     html! {
                         <tr>
                         <td>{ i + 1 }</td>
-                        <td>{ player }</td>
-                        <td>{ count }</td>
+                        <td>{ player_link(&player.name) }</td>
+                        <td>{ player.wins }</td>
                         </tr>
                     }
                 }).collect::<Html>() }
@@ -86,19 +161,25 @@ impl ScoresModel {
         }
     }
 
-    fn fetch_games(&mut self) -> FetchTask {
-        let callback =
-            self.link
-                .callback(move |response: Response<Json<Result<Vec<Game>, Error>>>| {
-                    let (meta, Json(data)) = response.into_parts();
-                    if meta.status.is_success() {
-                        Msg::FetchReady(data)
-                    } else {
-                        error!("Failed to fetch games");
+    // fetches url and hands the parsed body to message
+    fn fetch<T, F>(&mut self, url: &str, message: F) -> FetchTask
+    where
+        T: for<'de> Deserialize<'de> + 'static,
+        F: Fn(T) -> Msg + 'static,
+    {
+        let callback = self
+            .link
+            .callback(move |response: Response<Json<Result<T, Error>>>| {
+                let (meta, Json(data)) = response.into_parts();
+                match data {
+                    Ok(data) if meta.status.is_success() => message(data),
+                    _ => {
+                        error!("Failed to fetch statistics");
                         Msg::Ignore
                     }
-                });
-        let request = Request::get("/games").body(Nothing).unwrap();
+                }
+            });
+        let request = Request::get(url).body(Nothing).unwrap();
         self.fetch_service.fetch(request, callback).unwrap()
     }
 }
@@ -110,20 +191,29 @@ impl Component for ScoresModel {
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         let mut model = ScoresModel {
             fetch_service: FetchService::new(),
-            fetch_task: None,
+            fetch_tasks: Vec::new(),
             link,
-            data: None,
+            summary: None,
+            computer: None,
+            computer_wins: None,
+            players: None,
         };
-        model.fetch_task = Some(model.fetch_games());
+        let computer_wins = format!("/games?winner=Computer&limit={}", COMPUTER_WINS_SHOWN);
+        model.fetch_tasks = vec![
+            model.fetch("/stats/summary", Msg::SummaryReady),
+            model.fetch("/stats/computer", Msg::ComputerReady),
+            model.fetch(&computer_wins, Msg::ComputerWinsReady),
+            model.fetch("/stats/players", Msg::PlayersReady),
+        ];
         model
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            Msg::FetchReady(response) => {
-                self.data = response.map(|data| data).ok();
-                self.fetch_task = None;
-            }
+            Msg::SummaryReady(summary) => self.summary = Some(summary),
+            Msg::ComputerReady(records) => self.computer = Some(records),
+            Msg::ComputerWinsReady(page) => self.computer_wins = Some(page),
+            Msg::PlayersReady(players) => self.players = Some(players),
             Msg::Ignore => (),
         }
         true
@@ -141,18 +231,34 @@ impl Component for ScoresModel {
             <div><h4>{"Games Won by Computer"}</h4></div>
                 <table>
                     <tr>
+                        <th>{"Game Type"}</th>
                         <th>{"Total Games Played"}</th>
                         <th>{"Games Against Computer"}</th>
                         <th>{"Games Computer Won"}</th>
+                        <th>{"Draws"}</th>
                     </tr>
                     { self.view_total_games() }
                 </table>
             <br></br>
+            <div><h4>{"Computer Results by Difficulty"}</h4></div>
+                <table>
+                    <tr>
+                        <th>{"Game Type"}</th>
+                        <th>{"Difficulty"}</th>
+                        <th>{"Games"}</th>
+                        <th>{"Computer Won"}</th>
+                        <th>{"Computer Lost"}</th>
+                        <th>{"Draws"}</th>
+                    </tr>
+                    { self.view_difficulties() }
+                </table>
+            <br></br>
             <div><h4>{"Details of Games Won by Computer"}</h4></div>
                 <div id="game-stream">
                 <table>
                     <tr>
                         <th>{"Sl. No."}</th>
+                        <th>{"Game-ID"}</th>
                         <th>{"Game Type"}</th>
                         <th>{"Winner"}</th>
                         <th>{"Played Against"}</th>
@@ -167,7 +273,7 @@ impl Component for ScoresModel {
                 <table>
                     <tr>
                         <th>{"Sl. No."}</th>
-                        <th>{"Winner or Draw"}</th>
+                        <th>{"Winner"}</th>
                         <th>{"No. of Wins"}</th>
                     </tr>
                     { self.view_total_wins() }
@@ -177,5 +283,3 @@ impl Component for ScoresModel {
         }
     }
 }
-
-//NOTE: Backend work here i think
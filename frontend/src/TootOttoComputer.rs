@@ -1,21 +1,36 @@
 use crate::player::Player;
+use crate::saved_game::{self, SavedGame};
+use crate::session::fetch_account;
 use crate::toot_canvas::TootCanvasModel;
 use yew::html::InputData;
+use yew::services::fetch::{FetchService, FetchTask};
 use yew::{prelude::*, components::Select, virtual_dom::VNode, Properties};
 
 use crate::Connect4Computer::Difficulty::{self, *};
 
+// the board on this page, also the key its unfinished game is saved under
+const CANVAS_ID: &str = "toot_computer";
+
 pub struct TootOttoComputerModel {
     player: Player,
     difficulty: Difficulty,
     update_player_name: Callback<InputData>,
     update_difficulty: Callback<Difficulty>,
     start_game_callback: Callback<ClickEvent>,
+    resume_game_callback: Callback<ClickEvent>,
     update_letter: Callback<InputData>,
     end_game_callback: Callback<i64>,
     is_game_on: bool,
     disabled: bool,
     display_state: String,
+    fetch_service: FetchService,
+    session_task: Option<FetchTask>,
+    // unfinished game from an earlier visit, offered until a new game starts
+    saved: Option<SavedGame>,
+    // the saved game the board picks up from
+    resume: Option<SavedGame>,
+    // logged in player, who always plays as player
+    account: Option<String>,
     letter: String,
 }
 
@@ -24,10 +39,25 @@ pub enum Msg {
     NewPlayer(InputData),
     ChangeDifficulty(Difficulty),
     StartGame,
+    ResumeGame,
     EndGame,
+    SessionReady(Option<String>),
     UpdateLetter(InputData),
 }
 
+impl TootOttoComputerModel {
+    fn view_resume(&self) -> Html {
+        match &self.saved {
+            Some(saved) if !self.is_game_on => html! {
+                <button onclick=&self.resume_game_callback title="Resume Game">
+                    { format!("Resume {} Vs {} ({} moves in)", saved.player1, saved.player2, saved.moves.len()) }
+                </button>
+            },
+            _ => html! {},
+        }
+    }
+}
+
 impl Component for TootOttoComputerModel {
     type Message = Msg;
     type Properties = ();
@@ -37,17 +67,26 @@ impl Component for TootOttoComputerModel {
             value: "".to_string(),
         };
 
+        let mut fetch_service = FetchService::new();
+        let session_task = fetch_account(&mut fetch_service, &link, Msg::SessionReady);
+
         TootOttoComputerModel {
             player,
             difficulty: Easy,
             update_player_name: link.callback(|e: InputData| Msg::NewPlayer(e)),
             update_difficulty: link.callback(|e: Difficulty| Msg::ChangeDifficulty(e)),
             start_game_callback: link.callback(|e| Msg::StartGame),
+            resume_game_callback: link.callback(|e| Msg::ResumeGame),
             end_game_callback: link.callback(|e: i64| Msg::EndGame),
             update_letter: link.callback(|e: InputData| Msg::UpdateLetter(e)),
             is_game_on: false,
             disabled: false,
             display_state: "none".to_string(),
+            fetch_service,
+            session_task: Some(session_task),
+            saved: saved_game::load(CANVAS_ID),
+            resume: None,
+            account: None,
             letter: "T".to_string(),
         }
     }
@@ -60,14 +99,38 @@ impl Component for TootOttoComputerModel {
                 // update canvas
             }
             Msg::StartGame => {
+                saved_game::clear(CANVAS_ID);
+                self.saved = None;
+                self.resume = None;
                 self.is_game_on = true;
                 self.disabled = true;
                 self.display_state = "block".to_string();
             }
+            Msg::ResumeGame => {
+                if let Some(saved) = self.saved.take() {
+                    if self.account.is_none() {
+                        self.player.value = saved.player1.clone();
+                    }
+                    self.difficulty = saved.difficulty;
+                    self.resume = Some(saved);
+                    self.is_game_on = true;
+                    self.disabled = true;
+                    self.display_state = "block".to_string();
+                }
+            }
+            Msg::SessionReady(account) => {
+                if let Some(name) = &account {
+                    self.player.value = name.clone();
+                }
+                self.account = account;
+                self.session_task = None;
+            }
             Msg::EndGame => {
                 self.is_game_on = false;
                 self.disabled = false;
                 self.display_state = "none".to_string();
+                self.resume = None;
+                self.saved = saved_game::load(CANVAS_ID);
             }
             Msg::UpdateLetter(e) => {
                 self.letter = e.value.to_string();
@@ -94,11 +157,13 @@ impl Component for TootOttoComputerModel {
                         id="textbox1",
                         type="text",
                         placeholder="Player's Name",
+                        value = &self.player.value,
+                        disabled = { self.account.is_some() },
                         oninput = &self.update_player_name,
                     />
                     <Select<Difficulty> 
                         disabled = { self.disabled }
-                        selected = Some(Easy),
+                        selected = Some(self.difficulty),
                         options = { vec![Easy, Medium, Hard] }
                         onchange = &self.update_difficulty />
                     <button
@@ -108,6 +173,7 @@ impl Component for TootOttoComputerModel {
                         title="Start Game">
                     { "Start Game" }
                     </button>
+                    { self.view_resume() }
                 </div>
             </div>
             <br></br>
@@ -122,10 +188,11 @@ impl Component for TootOttoComputerModel {
                 <label for="O">{"O"}</label>
                 <br></br>
                 <TootCanvasModel: 
-                    canvas_id = "toot_computer" 
+                    canvas_id = CANVAS_ID,
                     player1 = self.player.value.clone(), 
                     player2="Computer", letter=self.letter.clone(),
                     difficulty = self.difficulty,
+                    resume = self.resume.clone(),
                     game_done_cbk=&self.end_game_callback/>
             </div>
             <br></br>
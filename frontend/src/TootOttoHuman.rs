@@ -1,10 +1,16 @@
 use crate::player::Player;
+use crate::saved_game::{self, SavedGame};
+use crate::session::fetch_account;
 use crate::toot_canvas::TootCanvasModel;
 use yew::html::InputData;
+use yew::services::fetch::{FetchService, FetchTask};
 use yew::{prelude::*, virtual_dom::VNode, Properties};
 
 use crate::Connect4Computer::Difficulty::Easy;
 
+// the board on this page, also the key its unfinished game is saved under
+const CANVAS_ID: &str = "toot_human";
+
 pub struct TootOttoHumanModel {
     player1: Player,
     player2: Player,
@@ -12,10 +18,19 @@ pub struct TootOttoHumanModel {
     update_player2_name: Callback<InputData>,
     update_letter: Callback<InputData>,
     start_game_callback: Callback<ClickEvent>,
+    resume_game_callback: Callback<ClickEvent>,
     end_game_callback: Callback<i64>,
     is_game_on: bool,
     disabled: bool,
     display_state: String,
+    fetch_service: FetchService,
+    session_task: Option<FetchTask>,
+    // unfinished game from an earlier visit, offered until a new game starts
+    saved: Option<SavedGame>,
+    // the saved game the board picks up from
+    resume: Option<SavedGame>,
+    // logged in player, who always plays as player1
+    account: Option<String>,
     letter: String,
 }
 
@@ -24,10 +39,25 @@ pub enum Msg {
     NewPlayer1(InputData),
     NewPlayer2(InputData),
     StartGame,
+    ResumeGame,
     EndGame,
+    SessionReady(Option<String>),
     UpdateLetter(InputData),
 }
 
+impl TootOttoHumanModel {
+    fn view_resume(&self) -> Html {
+        match &self.saved {
+            Some(saved) if !self.is_game_on => html! {
+                <button onclick=&self.resume_game_callback title="Resume Game">
+                    { format!("Resume {} Vs {} ({} moves in)", saved.player1, saved.player2, saved.moves.len()) }
+                </button>
+            },
+            _ => html! {},
+        }
+    }
+}
+
 impl Component for TootOttoHumanModel {
     type Message = Msg;
     type Properties = ();
@@ -41,6 +71,9 @@ impl Component for TootOttoHumanModel {
             value: "".to_string(),
         };
 
+        let mut fetch_service = FetchService::new();
+        let session_task = fetch_account(&mut fetch_service, &link, Msg::SessionReady);
+
         Self {
             player1,
             player2,
@@ -48,10 +81,16 @@ impl Component for TootOttoHumanModel {
             update_player2_name: link.callback(|e: InputData| Msg::NewPlayer2(e)),
             update_letter: link.callback(|e: InputData| Msg::UpdateLetter(e)),
             start_game_callback: link.callback(|e| Msg::StartGame),
+            resume_game_callback: link.callback(|e| Msg::ResumeGame),
             end_game_callback: link.callback(|e: i64| Msg::EndGame),
             is_game_on: false,
             disabled: false,
             display_state: "none".to_string(),
+            fetch_service,
+            session_task: Some(session_task),
+            saved: saved_game::load(CANVAS_ID),
+            resume: None,
+            account: None,
             letter: "T".to_string(),
         }
     }
@@ -61,14 +100,38 @@ impl Component for TootOttoHumanModel {
             Msg::NewPlayer1(val) => self.player1.value = val.value,
             Msg::NewPlayer2(val) => self.player2.value = val.value,
             Msg::StartGame => {
+                saved_game::clear(CANVAS_ID);
+                self.saved = None;
+                self.resume = None;
                 self.is_game_on = true;
                 self.disabled = true;
                 self.display_state = "block".to_string();
             }
+            Msg::ResumeGame => {
+                if let Some(saved) = self.saved.take() {
+                    if self.account.is_none() {
+                        self.player1.value = saved.player1.clone();
+                    }
+                    self.player2.value = saved.player2.clone();
+                    self.resume = Some(saved);
+                    self.is_game_on = true;
+                    self.disabled = true;
+                    self.display_state = "block".to_string();
+                }
+            }
+            Msg::SessionReady(account) => {
+                if let Some(name) = &account {
+                    self.player1.value = name.clone();
+                }
+                self.account = account;
+                self.session_task = None;
+            }
             Msg::EndGame => {
                 self.is_game_on = false;
                 self.disabled = false;
                 self.display_state = "none".to_string();
+                self.resume = None;
+                self.saved = saved_game::load(CANVAS_ID);
             }
             Msg::UpdateLetter(e) => {
                 self.letter = e.value.to_string();
@@ -95,12 +158,15 @@ impl Component for TootOttoHumanModel {
                         id="textbox1",
                         type="text",
                         placeholder="Player 1's Name",
+                        value = &self.player1.value,
+                        disabled = { self.account.is_some() },
                         oninput = &self.update_player1_name,
                     />
                     <input
                         id="textbox2",
                         type="text",
                         placeholder="Player 2's Name",
+                        value = &self.player2.value,
                         oninput = &self.update_player2_name,
                     />
                     <button
@@ -111,6 +177,7 @@ impl Component for TootOttoHumanModel {
                     >
                     { "Start Game" }
                     </button>
+                    { self.view_resume() }
                 </div>
             </div>
             <br></br>
@@ -125,11 +192,12 @@ impl Component for TootOttoHumanModel {
                 <label for="O">{"O"}</label>
                 <br></br>
                 <TootCanvasModel: 
-                    canvas_id="toot_human" 
+                    canvas_id = CANVAS_ID,
                     player1 = self.player1.value.clone(), 
                     player2=self.player2.value.clone(),
                     difficulty = Easy,
                     letter=self.letter.clone(), 
+                    resume = self.resume.clone(),
                     game_done_cbk=&self.end_game_callback/>
             </div>
             <br></br>
@@ -27,7 +27,7 @@ impl Component for WelcomeModel {
                     {"This application contains the following two board games, both in human Vs. human and human Vs. Computer versions."}
                 </p>
                 <ul>
-                    <li>{"Connect 4"}</li>
+                    <li>{"Connect 4, also as Pop-Out, Power-Up and 5-in-a-Row"}</li>
                     <li>{"TOOT-OTTO"}</li>
                 </ul>
                 <p>{"Select the game of your choice from the side bar, and start playing. Enjoy!"}</p>
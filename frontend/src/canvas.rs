@@ -10,8 +10,15 @@ use yew::format::Json;
 use yew::services::fetch::{FetchService, FetchTask, Request, Response};
 use yew::{prelude::*, virtual_dom::VNode, Properties};
 
+use engine::ai::connect4::Search;
+use engine::ai::connect_n;
+use engine::ai::solver::{Prediction, Solver, DEFAULT_NODE_LIMIT};
+use engine::{GameKind, GameState, Move, Outcome, Position, Power, Variant};
+
 use crate::player::Player;
 use crate::Connect4Computer::Difficulty::{self, *};
+use crate::offline::{self, Saved};
+use crate::saved_game::{self, SavedGame};
 use crate::ScoreBoard::Game;
 
 macro_rules! enclose {
@@ -30,13 +37,25 @@ pub struct CanvasModel {
     ctx: Option<CanvasRenderingContext2d>,
     cbk: Callback<ClickEvent>,
     animate_cbk: Callback<(usize, i64, usize, usize, bool)>,
-    map: Vec<Vec<i64>>,
-    current_move: i64,
+    pop_cbk: Callback<(usize, usize, bool)>,
+    state: GameState,
+    // the disc being animated
+    falling: Move,
+    // special disc picked for the next click in Power-Up
+    power: Option<Power>,
+    solver: Option<Solver>,
+    prediction: String,
+    animating: bool,
+    // moves taken back by undo, most recent last
+    undone: Vec<Move>,
+    used_undo: bool,
     won: bool,
     paused: bool,
     reject_click: bool,
     fetch_service: FetchService,
     fetch_task: Option<FetchTask>,
+    // how posting the finished game went
+    save_status: String,
     link: ComponentLink<CanvasModel>,
 }
 
@@ -47,201 +66,81 @@ pub struct Props {
     pub difficulty: Difficulty,
     pub canvas_id: Option<String>,
     pub game_done_cbk: Callback<i64>,
+    // recorded moves to step through instead of playing, the board shows the
+    // first replay_step of them
+    #[prop_or_default]
+    pub replay: Option<Vec<Move>>,
+    #[prop_or_default]
+    pub replay_step: usize,
+    // in online games the replay is the room's move list and only the local player's
+    // moves are taken from clicks, each is passed to move_cbk to send to the server
+    #[prop_or_default]
+    pub local_player: Option<engine::Player>,
+    #[prop_or_default]
+    pub move_cbk: Option<Callback<Move>>,
+    // an unfinished game to carry on with
+    #[prop_or_default]
+    pub resume: Option<SavedGame>,
+    // board size and line length, the board is cleared when it changes
+    #[prop_or_default]
+    pub variant: Variant,
+    // Pop-Out rules, a player may take one of their discs out of the bottom row
+    #[prop_or_default]
+    pub pop_out: bool,
+    // Power-Up rules, each player has one of every special disc
+    #[prop_or_default]
+    pub power_up: bool,
+    // 5-in-a-Row rules, the outer columns start full; variant is its 6x9 board
+    #[prop_or_default]
+    pub five_in_a_row: bool,
+}
+
+impl Props {
+    fn kind(&self) -> GameKind {
+        if self.pop_out {
+            GameKind::PopOut
+        } else if self.power_up {
+            GameKind::PowerUp
+        } else if self.five_in_a_row {
+            GameKind::FiveInARow
+        } else {
+            GameKind::Connect4
+        }
+    }
 }
 
 pub enum Message {
     Click(ClickEvent),
     AnimateCallback((usize, i64, usize, usize, bool)),
+    PopCallback((usize, usize, bool)),
+    ChoosePower(Power),
+    Undo,
+    Redo,
+    Saved(Saved),
     Ignore,
 }
 
 impl CanvasModel {
     pub fn reset(&mut self) {
-        self.map = vec![vec![0; 7]; 6];
-        self.current_move = 0;
+        self.state = GameState::with_variant(self.props.kind(), self.props.variant);
+        self.prediction = String::new();
+        self.power = None;
+        self.undone.clear();
+        self.used_undo = false;
         self.paused = false;
         self.won = false;
+        self.save_status = String::new();
         self.reject_click = false;
+        self.resize();
         self.clear();
         self.draw_mask();
     }
 
-    #[inline]
-    pub fn check_state(&self, state: &Vec<Vec<i64>>) -> (i64, i64) {
-        let mut win_val = 0;
-        let mut chain_val = 0;
-        let (mut temp_r, mut temp_b, mut temp_br, mut temp_tr) = (0, 0, 0, 0);
-        for i in 0..6 {
-            for j in 0..7 {
-                temp_r = 0;
-                temp_b = 0;
-                temp_br = 0;
-                temp_tr = 0;
-                for k in 0..=3 {
-                    if j + k < 7 {
-                        temp_r += state[i][j + k];
-                    }
-
-                    if i + k < 6 {
-                        temp_b += state[i + k][j];
-                    }
-
-                    if i + k < 6 && j + k < 7 {
-                        temp_br += state[i + k][j + k];
-                    }
-
-                    if i >= k && j + k < 7 {
-                        temp_tr += state[i - k][j + k];
-                    }
-                }
-                chain_val += temp_r * temp_r * temp_r;
-                chain_val += temp_b * temp_b * temp_b;
-                chain_val += temp_br * temp_br * temp_br;
-                chain_val += temp_tr * temp_tr * temp_tr;
-
-                if temp_r.abs() == 4 {
-                    win_val = temp_r;
-                } else if temp_b.abs() == 4 {
-                    win_val = temp_b;
-                } else if temp_br.abs() == 4 {
-                    win_val = temp_br;
-                } else if temp_tr.abs() == 4 {
-                    win_val = temp_tr;
-                }
-            }
-        }
-
-        return (win_val, chain_val);
-    }
-
-    pub fn value(
-        &self,
-        ai_move_value: i64,
-        state: &Vec<Vec<i64>>,
-        depth: i64,
-        mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, i64) {
-        let val = self.check_state(state);
-        let max_depth = match self.props.difficulty {
-            Easy => 1,
-            Medium => 3,
-            Hard => 5,
-        };
-        info!("{:?}", self.props.difficulty);
-        if depth >= max_depth {
-            // if slow (or memory consumption is high), lower the value
-            let mut ret_val = 0;
-
-            // if win, value = +inf
-            let win_val = val.0;
-            let chain_val = val.1 * ai_move_value;
-            ret_val = chain_val;
-
-            // If it lead to winning, then do it
-            if win_val == 4 * ai_move_value {
-                // AI win, AI wants to win of course
-                ret_val = 999999;
-            } else if win_val == 4 * ai_move_value * -1 {
-                // AI lose, AI hates losing
-                ret_val = 999999 * -1;
-            }
-            ret_val -= depth * depth;
-
-            return (ret_val, -1);
-        }
-
-        let win = val.0;
-        // if already won, then return the value right away
-        if win == 4 * ai_move_value {
-            // AI win, AI wants to win of course
-            return (999999 - depth * depth, -1);
-        }
-        if win == 4 * ai_move_value * -1 {
-            // AI lose, AI hates losing
-            return (999999 * -1 - depth * depth, -1);
-        }
-
-        if depth % 2 == 0 {
-            return self.min_state(ai_move_value, state, depth + 1, alpha, beta);
-        }
-        return self.max_state(ai_move_value, state, depth + 1, alpha, beta);
-    }
-
-    pub fn max_state(
-        &self,
-        ai_move_value: i64,
-        state: &Vec<Vec<i64>>,
-        depth: i64,
-        mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, i64) {
-        let mut v = -100000000007;
-        let mut new_move: i64 = -1;
-        let mut move_queue = Vec::new();
-
-        for j in 0..7 {
-            let temp_state = self.fill_map(state, j, ai_move_value);
-            if temp_state[0][0] != 999 {
-                let temp_val = self.value(ai_move_value, &temp_state, depth, alpha, beta);
-                if temp_val.0 > v {
-                    v = temp_val.0;
-                    new_move = j as i64;
-                    move_queue = Vec::new();
-                    move_queue.push(j);
-                } else if temp_val.0 == v {
-                    move_queue.push(j);
-                }
-
-                // alpha-beta pruning
-                if v > beta {
-                    new_move = self.choose(&move_queue);
-                    return (v, new_move);
-                }
-                alpha = std::cmp::max(alpha, v);
-            }
-        }
-        new_move = self.choose(&move_queue);
-
-        return (v, new_move);
-    }
-
-    pub fn min_state(
-        &self,
-        ai_move_value: i64,
-        state: &Vec<Vec<i64>>,
-        depth: i64,
-        mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, i64) {
-        let mut v = 100000000007;
-        let mut new_move: i64 = -1;
-        let mut move_queue = Vec::new();
-
-        for j in 0..7 {
-            let temp_state = self.fill_map(state, j, ai_move_value * -1);
-            if temp_state[0][0] != 999 {
-                let temp_val = self.value(ai_move_value, &temp_state, depth, alpha, beta);
-                if temp_val.0 < v {
-                    v = temp_val.0;
-                    new_move = j as i64;
-                    move_queue = Vec::new();
-                    move_queue.push(j);
-                } else if temp_val.0 == v {
-                    move_queue.push(j);
-                }
-
-                // alpha-beta pruning
-                if v < alpha {
-                    new_move = self.choose(&move_queue);
-                    return (v, new_move);
-                }
-                beta = std::cmp::min(beta, v);
-            }
-        }
-        new_move = self.choose(&move_queue);
-
-        return (v, new_move);
+    // fits the canvas to the board, which clears it
+    fn resize(&self) {
+        let canvas = self.canvas.as_ref().unwrap();
+        canvas.set_width((75 * self.state.board().columns() + 115) as u32);
+        canvas.set_height((75 * self.state.board().rows() + 30) as u32);
     }
 
     #[inline]
@@ -259,47 +158,102 @@ impl CanvasModel {
         return choice[index] as i64;
     }
 
-    pub fn ai(&mut self, ai_move_value: i64) {
-        let new_map = self.map.clone();
-        let val_choice = self.max_state(ai_move_value, &new_map, 0, -100000000007, 100000000007);
-
-        let val = val_choice.0;
-        let choice = val_choice.1;
+    // solves the position and describes the theoretical result below the board
+    fn perfect_moves(&mut self, position: &Position) -> Vec<usize> {
+        let solver = self.solver.get_or_insert_with(Solver::new);
+        let (prediction, choices) = solver.best_moves(position);
+        info!(
+            "Perfect searched {} positions, transposition table: {}",
+            solver.nodes(),
+            solver.table_stats()
+        );
 
-        self.paused = false;
-        // TODO: Add rejectclick callback
-        let mut done = self.action(choice as usize, true);
+        self.prediction = match prediction {
+            Some(Prediction::Win(discs)) => format!(
+                "With perfect play {} wins within {} more discs",
+                self.props.player2.as_ref().unwrap(),
+                discs
+            ),
+            Some(Prediction::Loss(discs)) => format!(
+                "With perfect play {} wins within {} more discs",
+                self.props.player1.as_ref().unwrap(),
+                discs
+            ),
+            Some(Prediction::Draw) => String::from("With perfect play the game is a draw"),
+            None => String::from("Too early to solve, the computer is searching as deep as it can"),
+        };
+        choices
+    }
 
-        // TODO: Add rejectclick callback
-        while done < 0 {
-            log::info!("Using random agent");
-            let random_choice = self.get_random_val(7);
-            done = self.action(random_choice, true);
+    // Pop-Out, Power-Up and boards other than 6x7 with four in a row, 5-in-a-Row
+    // among them, are played by the slower general search
+    fn connect_n_moves(&mut self) -> Vec<Move> {
+        let mut search = connect_n::Search::new(&self.state);
+        let mut choices = match self.props.difficulty {
+            Easy => search.best_moves(&self.state, 2).1,
+            Medium => search.best_moves(&self.state, 4).1,
+            Hard => search.best_moves(&self.state, 6).1,
+            Perfect => {
+                self.prediction = String::from(
+                    "Only Connect-4 on the standard board can be solved, the computer is searching as deep as it can",
+                );
+                search.deepest_moves(&self.state, DEFAULT_NODE_LIMIT)
+            }
+        };
+        info!(
+            "{:?} searched {} positions on {}, transposition table: {}",
+            self.props.difficulty,
+            search.nodes(),
+            self.props.variant,
+            search.table_stats()
+        );
+        // special discs are kept for when they do better than a plain one
+        if choices.iter().any(|mv| mv.power.is_none()) {
+            choices.retain(|mv| mv.power.is_none());
         }
+        choices
     }
 
-    pub fn fill_map(&self, new_state: &Vec<Vec<i64>>, column: usize, value: i64) -> Vec<Vec<i64>> {
-        let mut temp_map = new_state.clone();
-        if temp_map[0][column] != 0 || column > 6 {
-            temp_map[0][0] = 999; // error code
+    pub fn ai(&mut self, ai_move_value: i64) {
+        if self.props.pop_out || self.props.power_up || !self.props.variant.is_standard() {
+            let choices = self.connect_n_moves();
+            self.play_ai(choices);
+            return;
         }
-
-        let mut done = false;
-        let mut row = 0;
-
-        for i in 0..5 {
-            if temp_map[i + 1][column] != 0 {
-                done = true;
-                row = i;
-                break;
+        let position = Position::from_moves(self.state.history()).unwrap();
+        let choices = match self.props.difficulty {
+            Perfect => self.perfect_moves(&position),
+            difficulty => {
+                let depth = match difficulty {
+                    Easy => 2,
+                    Medium => 6,
+                    Hard | Perfect => 10,
+                };
+                let mut search = Search::new();
+                let (_, choices) = search.best_moves(&position, depth);
+                info!(
+                    "{:?} searched {} positions, transposition table: {}",
+                    difficulty,
+                    search.nodes(),
+                    search.table_stats()
+                );
+                choices
             }
+        };
+        self.play_ai(choices.into_iter().map(Move::drop).collect());
+    }
+
+    // plays one of the search's best moves, which are always legal, or any legal move
+    // if the search found none
+    fn play_ai(&mut self, mut choices: Vec<Move>) {
+        self.paused = false;
+        if choices.is_empty() {
+            choices = self.state.legal_moves();
         }
-        if !done {
-            row = 5;
+        if !choices.is_empty() {
+            let choice = choices[self.get_random_val(choices.len())];
+            self.play(choice, true);
         }
-
-        temp_map[row][column] = value;
-        return temp_map;
     }
 
     pub fn draw_circle(&self, x: u32, y: u32, fill: &str, stroke: &str, text: &str) {
@@ -324,8 +278,8 @@ impl CanvasModel {
         self.ctx.as_ref().unwrap().save();
         self.ctx.as_ref().unwrap().set_fill_style_color("#00bfff");
         self.ctx.as_ref().unwrap().begin_path();
-        for y in 0..6 {
-            for x in 0..7 {
+        for y in 0..self.state.board().rows() {
+            for x in 0..self.state.board().columns() {
                 self.ctx.as_ref().unwrap().arc(
                     (75 * x + 100) as f64,
                     (75 * y + 50) as f64,
@@ -347,70 +301,60 @@ impl CanvasModel {
     }
 
     pub fn draw(&self) {
-        for y in 0..6 {
-            for x in 0..7 {
-                let mut fg_color = "transparent";
-                if self.map[y][x] >= 1 {
-                    fg_color = "#ff4136";
-                } else if self.map[y][x] <= -1 {
-                    fg_color = "#ffff00";
+        self.draw_except(None);
+    }
+
+    // every disc outside skip, a column being animated
+    fn draw_except(&self, skip: Option<usize>) {
+        for y in 0..self.state.board().rows() {
+            for x in 0..self.state.board().columns() {
+                if skip == Some(x) {
+                    continue;
                 }
-                self.draw_circle(
-                    (75 * x + 100) as u32,
-                    (75 * y + 50) as u32,
-                    &fg_color,
-                    "black",
-                    if self.map[y][x] >= 1 {
-                        "X"
-                    } else if self.map[y][x] <= -1 {
-                        "O"
-                    } else {
-                        ""
-                    },
-                );
+                let value = self
+                    .state
+                    .board()
+                    .get(y, x)
+                    .map_or(0, |cell| cell.player.value());
+                let power = self.state.board().get(y, x).and_then(|cell| cell.power);
+                self.draw_disc(x, 75 * y, value, power);
             }
         }
     }
 
-    pub fn check(&mut self) {
-        let (mut temp_r, mut temp_b, mut temp_br, mut temp_tr) = (0, 0, 0, 0);
-        for i in 0..6 {
-            for j in 0..7 {
-                temp_r = 0;
-                temp_b = 0;
-                temp_br = 0;
-                temp_tr = 0;
-                for k in 0..=3 {
-                    if j + k < 7 {
-                        temp_r += self.map[i][j + k];
-                    }
-
-                    if i + k < 6 {
-                        temp_b += self.map[i + k][j];
-                    }
-
-                    if i + k < 6 && j + k < 7 {
-                        temp_br += self.map[i + k][j + k];
-                    }
-
-                    if i >= k && j + k < 7 {
-                        temp_tr += self.map[i - k][j + k];
-                    }
-                }
-                if temp_r.abs() == 4 {
-                    self.win(temp_r);
-                } else if temp_b.abs() == 4 {
-                    self.win(temp_b);
-                } else if temp_br.abs() == 4 {
-                    self.win(temp_br);
-                } else if temp_tr.abs() == 4 {
-                    self.win(temp_tr);
-                }
-            }
+    // a disc for the player with value, top pixels down the board; special discs
+    // are marked with their power and walls are gray for neither player
+    fn draw_disc(&self, column: usize, top: usize, value: i64, power: Option<Power>) {
+        let mut fg_color = "transparent";
+        if power == Some(Power::Wall) {
+            fg_color = "#aaaaaa";
+        } else if value >= 1 {
+            fg_color = "#ff4136";
+        } else if value <= -1 {
+            fg_color = "#ffff00";
         }
-        // check if draw
-        if (self.current_move == 42) && (!self.won) {
-            self.win(0);
+        self.draw_circle(
+            (75 * column + 100) as u32,
+            (top + 50) as u32,
+            &fg_color,
+            "black",
+            match power {
+                Some(Power::Anvil) => "A",
+                Some(Power::Bomb) => "B",
+                Some(Power::Wall) => "W",
+                Some(Power::Double) => "x2",
+                None if value >= 1 => "X",
+                None if value <= -1 => "O",
+                None => "",
+            },
+        );
+    }
+
+    pub fn check(&mut self) {
+        match self.state.outcome() {
+            Some(Outcome::Win(player)) => self.win(player.value()),
+            Some(Outcome::Draw) => self.win(0),
+            None => {}
         }
     }
 
@@ -428,10 +372,7 @@ impl CanvasModel {
     }
 
     pub fn player_move(&self) -> i64 {
-        if self.current_move % 2 == 0 {
-            return 1;
-        }
-        return -1;
+        self.state.to_move().value()
     }
 
     pub fn animate(
@@ -442,23 +383,10 @@ impl CanvasModel {
         cur_pos: usize,
         mode: bool,
     ) {
-        let mut fg_color = "transparent";
-        if current_move >= 1 {
-            fg_color = "#ff4136";
-        } else if current_move <= -1 {
-            fg_color = "#ffff00";
-        }
-
         if to_row * 75 >= cur_pos {
             self.clear();
             self.draw();
-            self.draw_circle(
-                (75 * column + 100) as u32,
-                (cur_pos + 50) as u32,
-                &fg_color,
-                "black",
-                if self.player_move() == 1 { "X" } else { "O" },
-            );
+            self.draw_disc(column, cur_pos, current_move, self.falling.power);
             self.draw_mask();
 
             let cloned = self.animate_cbk.clone();
@@ -466,41 +394,286 @@ impl CanvasModel {
                 cloned.emit((column, current_move, to_row, cur_pos+25, mode));
             }));
         } else {
-            self.map[to_row][column] = self.player_move();
-            self.current_move += 1;
-            self.draw();
-            self.check();
-            if mode == false && self.props.player2.as_ref().unwrap() == "Computer" {
-                self.ai(-1);
-            } else {
-                self.reject_click = false;
+            self.state.apply_move(self.falling).unwrap();
+            // a bomb or anvil leaves the board emptier than it was drawn
+            self.clear();
+            self.draw_mask();
+            self.moved(mode);
+        }
+    }
+
+    // the bottom disc of column drops out of the board and the ones above follow it
+    // down a row
+    pub fn animate_pop(&mut self, column: usize, offset: usize, mode: bool) {
+        if offset < 75 {
+            self.clear();
+            self.draw_except(Some(column));
+            for row in 0..self.state.board().rows() {
+                if let Some(cell) = self.state.board().get(row, column) {
+                    self.draw_disc(column, 75 * row + offset, cell.player.value(), cell.power);
+                }
             }
+            self.draw_mask();
+
+            let cloned = self.pop_cbk.clone();
+            window().request_animation_frame(enclose!((cloned) move |_| {
+                cloned.emit((column, offset + 25, mode));
+            }));
+        } else {
+            self.state.apply_move(Move::pop(column)).unwrap();
+            self.clear();
+            self.draw_mask();
+            self.moved(mode);
         }
     }
 
-    pub fn action(&mut self, column: usize, mode: bool) -> i64 {
-        if self.paused || self.won {
-            return 0;
+    // the last move has been played out on the board, mode is set for the computer's
+    // and replayed moves
+    fn moved(&mut self, mode: bool) {
+        self.animating = false;
+        self.draw();
+        self.check();
+        if self.props.replay.is_some() {
+            // the step may have moved on while the disc was falling
+            self.reject_click = false;
+            self.sync_replay();
+        } else if self.vs_computer() && !self.won && self.state.to_move() == engine::Player::Two {
+            // after an x2 disc the same side moves again
+            self.ai(-1);
+        } else {
+            self.reject_click = false;
+            self.save_progress();
+        }
+    }
+
+    // keeps the unfinished game for the page to offer resuming, against the computer
+    // only once it has replied
+    fn save_progress(&self) {
+        if self.props.replay.is_some() || self.won {
+            return;
+        }
+        if self.vs_computer() && self.state.to_move() != engine::Player::One {
+            return;
+        }
+        if self.state.move_count() == 0 {
+            saved_game::clear(&self.canvas_id);
+            return;
         }
+        let saved = SavedGame {
+            player1: self.props.player1.clone().unwrap_or_default(),
+            player2: self.props.player2.clone().unwrap_or_default(),
+            difficulty: self.props.difficulty,
+            variant: self.props.variant,
+            pop_out: self.props.pop_out,
+            power_up: self.props.power_up,
+            five_in_a_row: self.props.five_in_a_row,
+            moves: self.state.history().to_vec(),
+            used_undo: self.used_undo,
+        };
+        saved_game::save(&self.canvas_id, &saved);
+    }
 
-        if self.map[0][column] != 0 || column > 6 {
-            return -1;
+    // puts the board back to a saved game
+    fn resume(&mut self, saved: &SavedGame) {
+        self.reset();
+        for &mv in &saved.moves {
+            if self.state.apply_move(mv).is_err() {
+                break;
+            }
+        }
+        self.used_undo = saved.used_undo;
+        self.redraw();
+        self.check();
+    }
+
+    // against the computer a turn is the player's move and the reply to it
+    fn vs_computer(&self) -> bool {
+        self.props.player2.as_ref().map_or(false, |name| name == "Computer")
+    }
+
+    fn can_undo(&self) -> bool {
+        !self.animating && !self.won && self.state.move_count() > 0
+    }
+
+    fn can_redo(&self) -> bool {
+        !self.animating && !self.won && !self.undone.is_empty()
+    }
+
+    // takes back the last turn, leaving the board with the same player to move
+    pub fn undo(&mut self) {
+        if !self.can_undo() {
+            return;
         }
+        while let Some(mv) = self.state.undo_move() {
+            self.undone.push(mv);
+            if !self.vs_computer() || self.state.to_move() == engine::Player::One {
+                break;
+            }
+        }
+        self.used_undo = true;
+        self.prediction = String::new();
+        self.power = None;
+        self.redraw();
+        self.save_progress();
+    }
 
-        let mut done = false;
-        let mut row = 0;
-        for i in 0..5 {
-            if self.map[i + 1][column] != 0 {
-                done = true;
-                row = i;
+    // plays back the last turn taken back by undo
+    pub fn redo(&mut self) {
+        if !self.can_redo() {
+            return;
+        }
+        while let Some(mv) = self.undone.pop() {
+            if self.state.apply_move(mv).is_err() {
+                self.undone.clear();
+                break;
+            }
+            if !self.vs_computer() || self.state.to_move() == engine::Player::One {
                 break;
             }
         }
-        if !done {
-            row = 5;
+        self.redraw();
+        self.check();
+        self.save_progress();
+    }
+
+    fn redraw(&mut self) {
+        self.paused = false;
+        self.reject_click = false;
+        self.clear();
+        self.draw();
+        self.draw_mask();
+    }
+
+    fn view_controls(&self) -> Html {
+        if self.props.replay.is_some() {
+            return self.view_powers();
+        }
+        let hint = if self.props.pop_out {
+            "Click one of your discs in the bottom row to pop it out"
+        } else {
+            ""
+        };
+        html! {
+            <div>
+                <button onclick=self.link.callback(|_| Message::Undo) disabled={!self.can_undo()}>{"Undo"}</button>
+                <button onclick=self.link.callback(|_| Message::Redo) disabled={!self.can_redo()}>{"Redo"}</button>
+                <small>{ hint }</small>
+                { self.view_powers() }
+            </div>
+        }
+    }
+
+    // the special discs the player to move has left, the picked one is played by the
+    // next click on the board
+    fn view_powers(&self) -> Html {
+        let local = match self.props.replay {
+            Some(_) => self.local_turn(),
+            None => !self.won && !(self.vs_computer() && self.state.to_move() == engine::Player::Two),
+        };
+        if !self.props.power_up || !local {
+            return html! {};
         }
+        let powers = self.state.powers_left(self.state.to_move());
+        html! {
+            <div>
+                {"Special discs: "}
+                { for powers.into_iter().map(|power| html! {
+                    <button
+                        onclick=self.link.callback(move |_| Message::ChoosePower(power))
+                        style={ if self.power == Some(power) { "font-weight: bold" } else { "" } }>
+                        { power.name() }
+                    </button>
+                }) }
+            </div>
+        }
+    }
 
-        self.animate(column, self.player_move(), row, 0, mode);
+    // an online game waiting for a click from this browser
+    fn local_turn(&self) -> bool {
+        let synced = match &self.props.replay {
+            Some(moves) => moves.len() == self.state.move_count(),
+            None => false,
+        };
+        synced
+            && !self.animating
+            && !self.won
+            && self.props.local_player == Some(self.state.to_move())
+    }
+
+    // brings the board to the first replay_step moves of the replay, a single step
+    // forward is animated and anything else is redrawn straight away
+    fn sync_replay(&mut self) {
+        let moves = match self.props.replay.clone() {
+            Some(moves) => moves,
+            None => return,
+        };
+        if self.animating {
+            return;
+        }
+
+        let step = std::cmp::min(self.props.replay_step, moves.len());
+        while self.state.move_count() > step || !moves.starts_with(self.state.history()) {
+            self.state.undo_move();
+        }
+        self.paused = false;
+        self.won = false;
+
+        if self.state.move_count() + 1 == step {
+            self.play(moves[step - 1], true);
+            return;
+        }
+        while self.state.move_count() < step {
+            let mv = moves[self.state.move_count()];
+            if self.state.apply_move(mv).is_err() {
+                break;
+            }
+        }
+        self.redraw();
+        self.check();
+    }
+
+    pub fn play(&mut self, mv: Move, mode: bool) -> i64 {
+        if mv.pop {
+            self.pop(mv.column, mode)
+        } else {
+            self.drop_piece(mv, mode)
+        }
+    }
+
+    // takes the side to move's disc out of the bottom of column, -1 if it isn't theirs
+    pub fn pop(&mut self, column: usize, mode: bool) -> i64 {
+        if self.paused || self.won {
+            return 0;
+        }
+        if self.state.validate(Move::pop(column)).is_err() {
+            return -1;
+        }
+
+        self.animating = true;
+        self.animate_pop(column, 0, mode);
+
+        self.paused = true;
+        return 1;
+    }
+
+    pub fn action(&mut self, column: usize, mode: bool) -> i64 {
+        self.drop_piece(Move::drop(column), mode)
+    }
+
+    // drops a plain or special disc into its column, -1 if the rules don't allow it
+    fn drop_piece(&mut self, mv: Move, mode: bool) -> i64 {
+        if self.paused || self.won {
+            return 0;
+        }
+
+        let row = match self.state.validate(mv) {
+            Ok(row) => row,
+            Err(_) => return -1,
+        };
+
+        self.falling = mv;
+        self.animating = true;
+        self.animate(mv.column, self.player_move(), row, 0, mode);
 
         self.paused = true;
         return 1;
@@ -520,7 +693,11 @@ impl CanvasModel {
             msg = "It's a draw".to_string();
         }
 
-        let to_print = format!("{} - Click on game board to reset", msg);
+        let to_print = if self.props.replay.is_some() {
+            msg
+        } else {
+            format!("{} - Click on game board to reset", msg)
+        };
 
         self.ctx.as_ref().unwrap().save();
         self.ctx.as_ref().unwrap().set_font("14pt sans-serif");
@@ -530,10 +707,18 @@ impl CanvasModel {
             .unwrap()
             .fill_text(&to_print, 150.0, 20.0, None);
 
+        // replays show a finished game, it has been saved already
+        if self.props.replay.is_some() {
+            self.ctx.as_ref().unwrap().restore();
+            return;
+        }
+
+        saved_game::clear(&self.canvas_id);
+
         // construct game to post
         let game = Game {
             gameNumber: String::new(),
-            gameType: String::from("Connect-4"),
+            gameType: self.props.kind().name().to_string(),
             Player1Name: self.props.player1.as_ref().unwrap().clone(),
             Player2Name: self.props.player2.as_ref().unwrap().clone(),
             WinnerName: if player > 0 {
@@ -544,24 +729,26 @@ impl CanvasModel {
                 String::from("Draw")
             },
             GameDate: Date::now() as u64,
+            Player1Inventory: None,
+            Player2Inventory: None,
+            Moves: self.state.history().to_vec(),
+            UsedUndo: self.used_undo,
+            Difficulty: if self.props.player2.as_ref().unwrap() == "Computer" {
+                Some(self.props.difficulty.to_string())
+            } else {
+                None
+            },
+            ClientId: Some(offline::new_client_id()),
+            Variant: Some(self.props.variant).filter(|&variant| variant != self.props.kind().variant()),
         };
 
-        // construct callback
-        let callback = self
-            .link
-            .callback(move |response: Response<Result<String, Error>>| {
-                info!("successfully saved!");
-                Message::Ignore
-            });
-
-        // construct request
-        let request = Request::post("/games")
-            .header("Content-Type", "application/json")
-            .body(Json(&game))
-            .unwrap();
-
-        // send the request
-        self.fetch_task = self.fetch_service.fetch(request, callback).ok();
+        // queued first, so the result survives the server being down
+        offline::enqueue(&game);
+        self.save_status = String::from("Saving...");
+        self.fetch_task = offline::send(&mut self.fetch_service, &self.link, &game, Message::Saved);
+        if self.fetch_task.is_none() {
+            self.save_status = Saved::Queued.message();
+        }
 
         self.ctx.as_ref().unwrap().restore();
     }
@@ -573,8 +760,7 @@ impl Component for CanvasModel {
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         let canvas_id = props.canvas_id.clone().unwrap();
-
-        let mut map: Vec<Vec<i64>> = vec![vec![0; 7]; 6];
+        let state = GameState::with_variant(props.kind(), props.variant);
 
         Self {
             props,
@@ -584,13 +770,21 @@ impl Component for CanvasModel {
             cbk: link.callback(|e: ClickEvent| Message::Click(e)),
             animate_cbk: link
                 .callback(|e: (usize, i64, usize, usize, bool)| Message::AnimateCallback(e)),
-            map,
-            current_move: 0,
+            pop_cbk: link.callback(|e: (usize, usize, bool)| Message::PopCallback(e)),
+            state,
+            falling: Move::drop(0),
+            power: None,
+            solver: None,
+            prediction: String::new(),
+            animating: false,
+            undone: Vec::new(),
+            used_undo: false,
             paused: false,
             won: false,
             reject_click: false,
             fetch_service: FetchService::new(),
             fetch_task: None,
+            save_status: String::new(),
             link,
         }
     }
@@ -598,7 +792,7 @@ impl Component for CanvasModel {
     fn update(&mut self, message: Self::Message) -> ShouldRender {
         match message {
             Message::Click(e) => {
-                if self.reject_click {
+                if self.reject_click || (self.props.replay.is_some() && !self.local_turn()) {
                     return false;
                 }
 
@@ -610,14 +804,32 @@ impl Component for CanvasModel {
 
                 let rect = self.canvas.as_ref().unwrap().get_bounding_client_rect();
                 let x = e.client_x() as f64 - rect.get_left();
+                let y = e.client_y() as f64 - rect.get_top();
+                let bottom = self.state.board().rows() - 1;
 
-                for j in 0..7 {
+                for j in 0..self.state.board().columns() {
                     if self.on_region(x, (75 * j + 100) as f64, 25 as f64) {
                         self.paused = false;
 
-                        let valid = self.action(j, false);
+                        // clicking one of your own discs in the bottom row pops it
+                        let mv = if let Some(power) = self.power {
+                            Move::power(j, power)
+                        } else if self.state.validate(Move::pop(j)).is_ok()
+                            && self.on_region(y, (75 * bottom + 50) as f64, 25 as f64)
+                        {
+                            Move::pop(j)
+                        } else {
+                            Move::drop(j)
+                        };
+                        let valid = self.play(mv, false);
                         if valid == 1 {
+                            self.power = None;
+                            if let Some(move_cbk) = &self.props.move_cbk {
+                                move_cbk.emit(mv);
+                            }
                             self.reject_click = true;
+                            // a new move replaces whatever was taken back
+                            self.undone.clear();
                         };
 
                         break;
@@ -627,6 +839,16 @@ impl Component for CanvasModel {
             Message::AnimateCallback((a, b, c, d, e)) => {
                 self.animate(a, b, c, d, e);
             }
+            Message::PopCallback((column, offset, mode)) => {
+                self.animate_pop(column, offset, mode);
+            }
+            // picking the same disc again goes back to a plain one
+            Message::ChoosePower(power) => {
+                self.power = if self.power == Some(power) { None } else { Some(power) };
+            }
+            Message::Undo => self.undo(),
+            Message::Redo => self.redo(),
+            Message::Saved(saved) => self.save_status = saved.message(),
             Message::Ignore => {}
         };
 
@@ -635,7 +857,12 @@ impl Component for CanvasModel {
 
     fn view(&self) -> Html {
         html! {
-            <canvas id={&self.canvas_id} height="480" width="640"></canvas>
+            <div>
+                <canvas id={&self.canvas_id}></canvas>
+                { self.view_controls() }
+                <p>{ &self.prediction }</p>
+                <p>{ &self.save_status }</p>
+            </div>
         }
     }
 
@@ -654,12 +881,31 @@ impl Component for CanvasModel {
 
         // clears and draws mask
         self.reset();
+        if let Some(saved) = self.props.resume.clone() {
+            self.resume(&saved);
+        }
+        self.sync_replay();
 
         true
     }
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let resume = props.resume.is_some() && props.resume != self.props.resume;
+        let rules = props.variant != self.props.variant
+            || props.pop_out != self.props.pop_out
+            || props.power_up != self.props.power_up
+            || props.five_in_a_row != self.props.five_in_a_row;
         self.props = props;
+        if self.ctx.is_some() {
+            if rules {
+                self.reset();
+            }
+            if resume {
+                let saved = self.props.resume.clone().unwrap();
+                self.resume(&saved);
+            }
+            self.sync_replay();
+        }
         true
     }
 }
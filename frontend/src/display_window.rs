@@ -1,8 +1,14 @@
+use crate::Account::AccountModel;
 use crate::Connect4Computer::Connect4ComputerModel;
 use crate::Connect4Human::Connect4HumanModel;
+use crate::GameDetails::{self, GameDetailsModel};
 use crate::HowToConnect4::HowToConnect4Model;
+use crate::HowToPowerUp::HowToPowerUpModel;
 use crate::HowToToot::HowToTootModel;
-use crate::ScoreBoard::ScoreBoardModel;
+use crate::Leaderboard::LeaderboardModel;
+use crate::Online::OnlineModel;
+use crate::Profile::{self, ProfileModel};
+use crate::ScoreBoard::{decode, ScoreBoardModel};
 use crate::Scores::ScoresModel;
 use crate::TootOttoComputer::TootOttoComputerModel;
 use crate::TootOttoHuman::TootOttoHumanModel;
@@ -47,13 +53,25 @@ impl Component for DisplayWindow {
             match uri.as_str() {
                 "" => return html! {<WelcomeModel/>},
                 "HowToConnect4" => return html! {<HowToConnect4Model/>},
+                "HowToPowerUp" => return html! {<HowToPowerUpModel/>},
                 "HowToToot" => return html! {<HowToTootModel/>},
                 "Connect4Computer" => return html! {<Connect4ComputerModel/>},
                 "Connect4Human" => return html! {<Connect4HumanModel/>},
                 "TootOttoComputer" => return html! {<TootOttoComputerModel/>},
                 "TootOttoHuman" => return html! {<TootOttoHumanModel/>},
+                "Online" => return html! {<OnlineModel/>},
                 "ScoreBoard" => return html! {<ScoreBoardModel/>},
                 "Scores" => return html! {<ScoresModel/>},
+                "Leaderboard" => return html! {<LeaderboardModel/>},
+                "Account" => return html! {<AccountModel/>},
+                profile if profile.starts_with(Profile::ROUTE_PREFIX) => {
+                    let name = decode(&profile[Profile::ROUTE_PREFIX.len()..]);
+                    return html! {<ProfileModel name=name/>};
+                }
+                game if game.starts_with(GameDetails::ROUTE_PREFIX) => {
+                    let id = decode(&game[GameDetails::ROUTE_PREFIX.len()..]);
+                    return html! {<GameDetailsModel id=id/>};
+                }
                 _ => {
                     return html! {"Page not found"};
                 }
@@ -1,9 +1,16 @@
 #![recursion_limit = "512"]
 #![allow(warnings)]
+mod Account;
 mod Connect4Computer;
 mod Connect4Human;
+mod GameDetails;
 mod HowToConnect4;
+mod HowToPowerUp;
 mod HowToToot;
+mod Leaderboard;
+mod Online;
+mod Profile;
+mod Replay;
 mod ScoreBoard;
 mod Scores;
 mod TootOttoComputer;
@@ -11,8 +18,11 @@ mod TootOttoHuman;
 mod Welcome;
 mod canvas;
 mod display_window;
+mod offline;
 mod page;
 mod player;
+mod saved_game;
+mod session;
 mod sidebar;
 mod toot_canvas;
 
@@ -1,3 +1,5 @@
+#![recursion_limit = "256"]
+
 use frontend::{Page, Sidebar};
 use stdweb::traits::*;
 use stdweb::unstable::TryInto;
@@ -47,6 +49,11 @@ impl Component for Model {
                     page_url="/#/Connect4Human"
                     title="Play Connect4 with Another Human"
                 />
+                <Page
+                    uri="HowToPowerUp"
+                    page_url="/#/HowToPowerUp"
+                    title="How to Play Power-Up"
+                />
                 <Page
                      uri="HowToToot"
                      page_url="/#/HowToToot"
@@ -62,6 +69,11 @@ impl Component for Model {
                     page_url="/#/TootOttoHuman"
                     title="Play Toot-Otto With Another Human"
                 />
+                <Page
+                    uri="Online"
+                    page_url="/#/Online"
+                    title="Play Online"
+                />
                 <Page
                     uri="ScoreBoard"
                     page_url="/#/ScoreBoard"
@@ -72,6 +84,16 @@ impl Component for Model {
                     page_url="/#/Scores"
                     title="Score Board"
                 />
+                <Page
+                    uri="Leaderboard"
+                    page_url="/#/Leaderboard"
+                    title="Leaderboard"
+                />
+                <Page
+                    uri="Account"
+                    page_url="/#/Account"
+                    title="Log In / Account"
+                />
             </Sidebar>
         }
     }
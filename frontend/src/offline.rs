@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use stdweb::unstable::TryInto;
+use yew::format::Json;
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::services::interval::IntervalTask;
+use yew::services::storage::{Area, StorageService};
+use yew::services::IntervalService;
+use yew::{html, Component, ComponentLink, Html, ShouldRender};
+
+use crate::ScoreBoard::{error_message, Game};
+
+// localStorage key of the finished games the server hasn't confirmed yet
+const KEY: &str = "pendingGames";
+
+// how often queued games are retried
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+// how posting a game went
+pub enum Saved {
+    Stored,
+    // the server couldn't be reached or failed, the game stays queued for a retry
+    Queued,
+    // the server refused the game, sending it again won't help
+    Rejected(String),
+}
+
+impl Saved {
+    pub fn message(&self) -> String {
+        match self {
+            Saved::Stored => String::from("Game saved"),
+            Saved::Queued => String::from("Saved on this device, it will be sent once the server is reachable"),
+            Saved::Rejected(error) => format!("The game wasn't saved: {}", error),
+        }
+    }
+}
+
+fn storage() -> Option<StorageService> {
+    StorageService::new(Area::Local).ok()
+}
+
+// the queued games, oldest first
+pub fn pending() -> Vec<Game> {
+    let storage = match storage() {
+        Some(storage) => storage,
+        None => return Vec::new(),
+    };
+    let Json(games) = storage.restore(KEY);
+    games.unwrap_or_default()
+}
+
+fn store(games: Vec<Game>) {
+    if let Some(mut storage) = storage() {
+        if games.is_empty() {
+            storage.remove(KEY);
+        } else {
+            storage.store(KEY, Json(&games));
+        }
+    }
+}
+
+pub fn enqueue(game: &Game) {
+    let mut games = pending();
+    if !games.iter().any(|queued| queued.ClientId == game.ClientId) {
+        games.push(game.clone());
+        store(games);
+    }
+}
+
+fn remove(client_id: &Option<String>) {
+    let mut games = pending();
+    games.retain(|game| game.ClientId != *client_id);
+    store(games);
+}
+
+// unique enough to tell this browser's games apart
+pub fn new_client_id() -> String {
+    let id = js! {
+        return Date.now().toString(36) + "-" + Math.random().toString(36).slice(2);
+    };
+    id.try_into().unwrap_or_default()
+}
+
+// posts a queued game, taking it off the queue once the server has decided on it
+pub fn send<COMP, F>(
+    fetch_service: &mut FetchService,
+    link: &ComponentLink<COMP>,
+    game: &Game,
+    message: F,
+) -> Option<FetchTask>
+where
+    COMP: Component,
+    F: Fn(Saved) -> COMP::Message + 'static,
+{
+    let client_id = game.ClientId.clone();
+    let callback = link.callback(move |response: Response<Result<String, Error>>| {
+        let (meta, body) = response.into_parts();
+        // yew reports a request that never got an answer as 408
+        let saved = match meta.status.as_u16() {
+            _ if meta.status.is_success() => Saved::Stored,
+            408 | 429 => Saved::Queued,
+            _ if meta.status.is_client_error() => Saved::Rejected(error_message(body)),
+            _ => Saved::Queued,
+        };
+        if let Saved::Stored | Saved::Rejected(_) = saved {
+            remove(&client_id);
+        }
+        message(saved)
+    });
+    let request = Request::post("/games")
+        .header("Content-Type", "application/json")
+        .body(Json(game))
+        .unwrap();
+    fetch_service.fetch(request, callback).ok()
+}
+
+pub enum Msg {
+    Retry,
+    Sent(Saved),
+}
+
+// retries queued games in the background and shows how many are waiting
+pub struct SyncIndicator {
+    pending: usize,
+    // the last retry couldn't reach the server
+    offline: bool,
+    fetch_service: FetchService,
+    fetch_task: Option<FetchTask>,
+    _retry: IntervalTask,
+    link: ComponentLink<SyncIndicator>,
+}
+
+impl SyncIndicator {
+    fn retry(&mut self) {
+        let games = pending();
+        self.pending = games.len();
+        if self.fetch_task.is_some() {
+            return;
+        }
+        if let Some(game) = games.first() {
+            self.fetch_task = send(&mut self.fetch_service, &self.link, game, Msg::Sent);
+        }
+    }
+}
+
+impl Component for SyncIndicator {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let retry = IntervalService::new().spawn(RETRY_INTERVAL, link.callback(|_| Msg::Retry));
+        let mut indicator = SyncIndicator {
+            pending: 0,
+            offline: false,
+            fetch_service: FetchService::new(),
+            fetch_task: None,
+            _retry: retry,
+            link,
+        };
+        indicator.retry();
+        indicator
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Retry => self.retry(),
+            Msg::Sent(saved) => {
+                self.fetch_task = None;
+                self.offline = match saved {
+                    Saved::Queued => true,
+                    Saved::Stored => false,
+                    Saved::Rejected(error) => {
+                        error!("Dropped a queued game: {}", error);
+                        false
+                    }
+                };
+                // keep going while the server answers
+                if !self.offline {
+                    self.retry();
+                } else {
+                    self.pending = pending().len();
+                }
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        if self.pending == 0 {
+            return html! {};
+        }
+        let games = if self.pending == 1 { "game" } else { "games" };
+        html! {
+            <div class="w3-padding w3-small">
+                { format!("{} {} waiting to sync", self.pending, games) }
+                { if self.offline { " (server unreachable)" } else { "" } }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::services::storage::{Area, StorageService};
+
+use engine::{Move, Variant};
+
+use crate::Connect4Computer::Difficulty;
+
+// an unfinished game kept in localStorage, the board and the side to move follow from
+// the moves
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SavedGame {
+    pub player1: String,
+    pub player2: String,
+    pub difficulty: Difficulty,
+    // games saved before boards could be resized are on the standard one
+    #[serde(default)]
+    pub variant: Variant,
+    #[serde(default)]
+    pub pop_out: bool,
+    #[serde(default)]
+    pub power_up: bool,
+    #[serde(default)]
+    pub five_in_a_row: bool,
+    pub moves: Vec<Move>,
+    pub used_undo: bool,
+}
+
+// each game page keeps one game, under its canvas id
+fn key(canvas_id: &str) -> String {
+    format!("savedGame.{}", canvas_id)
+}
+
+fn storage() -> Option<StorageService> {
+    StorageService::new(Area::Local).ok()
+}
+
+pub fn load(canvas_id: &str) -> Option<SavedGame> {
+    let Json(saved): Json<Result<SavedGame, Error>> = storage()?.restore(&key(canvas_id));
+    saved.ok()
+}
+
+pub fn save(canvas_id: &str, saved: &SavedGame) {
+    if let Some(mut storage) = storage() {
+        storage.store(&key(canvas_id), Json(saved));
+    }
+}
+
+pub fn clear(canvas_id: &str) {
+    if let Some(mut storage) = storage() {
+        storage.remove(&key(canvas_id));
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Error;
+use serde::Deserialize;
+use yew::format::{Json, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::{Component, ComponentLink};
+
+// GET /session, the logged in player
+#[derive(Deserialize, Debug)]
+pub struct Account {
+    pub name: String,
+}
+
+// asks the server who is logged in, message gets None when nobody is
+pub fn fetch_account<COMP, F>(
+    fetch_service: &mut FetchService,
+    link: &ComponentLink<COMP>,
+    message: F,
+) -> FetchTask
+where
+    COMP: Component,
+    F: Fn(Option<String>) -> COMP::Message + 'static,
+{
+    let callback = link.callback(move |response: Response<Json<Result<Account, Error>>>| {
+        let (meta, Json(data)) = response.into_parts();
+        match data {
+            Ok(account) if meta.status.is_success() => message(Some(account.name)),
+            _ => message(None),
+        }
+    });
+    let request = Request::get("/session").body(Nothing).unwrap();
+    fetch_service.fetch(request, callback).unwrap()
+}
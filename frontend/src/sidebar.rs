@@ -1,5 +1,6 @@
 use crate::{
     display_window::DisplayWindow,
+    offline::SyncIndicator,
     page::{Page, PageProps},
 };
 
@@ -62,7 +63,9 @@ impl Component for Sidebar {
                         None
                     }
                 })
-                .next();
+                .next()
+                // pages outside the menu, like player profiles, go by their path
+                .or_else(|| route.to_string().strip_prefix("/#/").map(String::from));
 
             let list_items = self
                 .props
@@ -78,6 +81,7 @@ impl Component for Sidebar {
                         </div>
 
                         {for list_items}
+                        <SyncIndicator/>
                     </nav>
                     <header class="w3-container w3-top w3-hide-large w3-red w3-xlarge w3-padding">
                     <a href="javascript:void(0)" class="w3-btn w3-red w3-border w3-border-white w3-margin-right">{"\u{2630}"}</a>
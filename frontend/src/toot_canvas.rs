@@ -1,6 +1,5 @@
 use anyhow::Error;
 use serde_json::json;
-use std::iter::FromIterator;
 use stdweb::traits::*;
 use stdweb::unstable::TryInto;
 use stdweb::web::event::{MouseMoveEvent, ResizeEvent};
@@ -12,8 +11,12 @@ use yew::format::Json;
 use yew::services::fetch::{FetchService, FetchTask, Request, Response};
 use yew::{prelude::*, virtual_dom::VNode, Properties};
 
-use crate::player::Player;
+use engine::ai::toot_otto::Search;
+use engine::{GameKind, GameState, Letter, Move, Outcome, Player, Variant};
+
 use crate::Connect4Computer::Difficulty::{self, *};
+use crate::offline::{self, Saved};
+use crate::saved_game::{self, SavedGame};
 use crate::ScoreBoard::Game;
 
 macro_rules! enclose {
@@ -32,15 +35,19 @@ pub struct TootCanvasModel {
     ctx: Option<CanvasRenderingContext2d>,
     cbk: Callback<ClickEvent>,
     animate_cbk: Callback<(usize, i64, char, usize, usize, bool)>,
-    map: Vec<Vec<i64>>,
-    dummy_map: Vec<Vec<char>>,
-    current_move: i64,
+    state: GameState,
+    animating: bool,
+    // moves taken back by undo, most recent last
+    undone: Vec<Move>,
+    used_undo: bool,
     won: bool,
     paused: bool,
     reject_click: bool,
     letter: String,
     fetch_service: FetchService,
     fetch_task: Option<FetchTask>,
+    // how posting the finished game went
+    save_status: String,
     link: ComponentLink<TootCanvasModel>,
 }
 
@@ -52,202 +59,45 @@ pub struct Props {
     pub canvas_id: Option<String>,
     pub game_done_cbk: Callback<i64>,
     pub letter: String,
+    // recorded moves to step through instead of playing, the board shows the
+    // first replay_step of them
+    #[prop_or_default]
+    pub replay: Option<Vec<Move>>,
+    #[prop_or_default]
+    pub replay_step: usize,
+    // in online games the replay is the room's move list and only the local player's
+    // moves are taken from clicks, each is passed to move_cbk to send to the server
+    #[prop_or_default]
+    pub local_player: Option<engine::Player>,
+    #[prop_or_default]
+    pub move_cbk: Option<Callback<Move>>,
+    // an unfinished game to carry on with
+    #[prop_or_default]
+    pub resume: Option<SavedGame>,
 }
 
 pub enum Message {
     Click(ClickEvent),
     AnimateCallback((usize, i64, char, usize, usize, bool)),
+    Undo,
+    Redo,
+    Saved(Saved),
     Ignore,
 }
 
 impl TootCanvasModel {
     pub fn reset(&mut self) {
-        self.map = vec![vec![0; 7]; 6];
-        self.dummy_map = vec![vec!['a'; 7]; 6];
-        self.current_move = 0;
+        self.state = GameState::new(GameKind::TootOtto);
+        self.undone.clear();
+        self.used_undo = false;
         self.paused = false;
         self.won = false;
+        self.save_status = String::new();
         self.reject_click = false;
         self.clear();
         self.draw_mask();
     }
 
-    #[inline]
-    pub fn check_state(&self, state: &Vec<Vec<i64>>) -> (i64, i64) {
-        let mut win_val = 0;
-        let mut chain_val = 0;
-        let (mut temp_r, mut temp_b, mut temp_br, mut temp_tr) = (0, 0, 0, 0);
-        for i in 0..6 {
-            for j in 0..7 {
-                temp_r = 0;
-                temp_b = 0;
-                temp_br = 0;
-                temp_tr = 0;
-                for k in 0..=3 {
-                    // TODO may need to be flipped
-                    let sign: i64 = if k == 0 || k == 3 { -1 } else { 1 };
-                    if j + k < 7 {
-                        temp_r += sign * state[i][j + k];
-                    }
-                    if i + k < 6 {
-                        temp_b += sign * state[i + k][j];
-                    }
-                    if i + k < 6 && j + k < 7 {
-                        temp_br += sign * state[i + k][j + k];
-                    }
-
-                    if i >= k && j + k < 7 {
-                        temp_tr += sign * state[i - k][j + k];
-                    }
-                }
-                chain_val += temp_r * temp_r * temp_r;
-                chain_val += temp_b * temp_b * temp_b;
-                chain_val += temp_br * temp_br * temp_br;
-                chain_val += temp_tr * temp_tr * temp_tr;
-
-                if temp_r.abs() == 4 {
-                    win_val = temp_r;
-                } else if temp_b.abs() == 4 {
-                    win_val = temp_b;
-                } else if temp_br.abs() == 4 {
-                    win_val = temp_br;
-                } else if temp_tr.abs() == 4 {
-                    win_val = temp_tr;
-                }
-            }
-        }
-        return (win_val, chain_val);
-    }
-
-    pub fn value(&self, state: &Vec<Vec<i64>>, depth: i64, mut alpha: i64, mut beta: i64) -> (i64) {
-        let val = self.check_state(state);
-        let max_depth = match self.props.difficulty {
-            Easy => 1,
-            Medium => 3,
-            Hard => 5,
-        };
-        if depth >= max_depth {
-            // if slow (or memory consumption is high), lower the value
-            let mut ret_val = 0;
-
-            // if win, value = +inf
-            let win_val = val.0;
-            let chain_val = val.1;
-            ret_val = chain_val;
-
-            // If it lead to winning, then do it
-            if win_val == 4 {
-                // AI win, AI wants to win of course
-                ret_val = 999999;
-            } else if win_val == 4 {
-                // AI lose, AI hates losing
-                ret_val = 999999 * -1;
-            }
-            ret_val -= depth * depth;
-
-            return (ret_val);
-        }
-
-        let win = val.0;
-        // if already won, then return the value right away
-        if win == 4 {
-            // AI win, AI wants to win of course
-            return (999999 - depth * depth);
-        }
-        if win == -4 {
-            // AI lose, AI hates losing
-            return (-999999 - depth * depth);
-        }
-
-        if depth % 2 == 0 {
-            return self.min_state(state, depth + 1, alpha, beta).0;
-        } else {
-            return self.max_state(state, depth + 1, alpha, beta).0;
-        }
-    }
-
-    // TODO - add letter to AI search
-    pub fn max_state(
-        &self,
-        state: &Vec<Vec<i64>>,
-        depth: i64,
-        mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, (i64, char)) {
-        let mut v = -100000000007;
-        let mut new_move: (i64, char) = (-1, ' ');
-        let mut move_queue = Vec::new();
-
-        for letter in &['T', 'O'] {
-            for j in 0..7 {
-                let move_value = if *letter == 'T' { 1 } else { -1 };
-                let temp_state = self.fill_map(state, j, move_value);
-                if temp_state[0][0] != 999 {
-                    let temp_val = self.value(&temp_state, depth, alpha, beta);
-                    if temp_val > v {
-                        v = temp_val;
-                        new_move = (j as i64, *letter);
-                        move_queue = Vec::new();
-                        move_queue.push((j as i64, *letter));
-                    } else if temp_val == v {
-                        move_queue.push(((j as i64, *letter)));
-                    }
-
-                    // alpha-beta pruning
-                    if v > beta {
-                        new_move = self.choose(&move_queue);
-                        return (v, new_move);
-                    }
-                    alpha = std::cmp::max(alpha, v);
-                }
-            }
-        }
-        new_move = self.choose(&move_queue);
-
-        return (v, new_move);
-    }
-
-    // TODO - add letter choice to search
-    pub fn min_state(
-        &self,
-        state: &Vec<Vec<i64>>,
-        depth: i64,
-        mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, (i64, char)) {
-        let mut v = 100000000007;
-        let mut new_move: (i64, char) = (-1, ' ');
-        let mut move_queue = Vec::new();
-
-        for letter in &['T', 'O'] {
-            for j in 0..7 {
-                let move_value = if *letter == 'T' { 1 } else { -1 };
-                let temp_state = self.fill_map(state, j, move_value);
-                if temp_state[0][0] != 999 {
-                    let temp_val = self.value(&temp_state, depth, alpha, beta);
-                    if temp_val < v {
-                        v = temp_val;
-                        new_move = (j as i64, *letter);
-                        move_queue = Vec::new();
-                        move_queue.push((j as i64, *letter));
-                    } else if temp_val == v {
-                        move_queue.push((j as i64, *letter));
-                    }
-
-                    // alpha-beta pruning
-                    if v < alpha {
-                        new_move = self.choose(&move_queue);
-                        return (v, new_move);
-                    }
-                    beta = std::cmp::min(beta, v);
-                }
-            }
-        }
-        new_move = self.choose(&move_queue);
-
-        return (v, new_move);
-    }
-
     #[inline]
     pub fn get_random_val(&self, val: usize) -> usize {
         let rand = js! { return Math.random(); };
@@ -264,10 +114,26 @@ impl TootCanvasModel {
     }
 
     pub fn ai(&mut self, ai_move_value: i64) {
-        let new_map = self.map.clone();
-        let val_choice = self.max_state(&new_map, 0, -100000000007, 100000000007);
+        let depth = match self.props.difficulty {
+            Easy => 2,
+            Medium => 4,
+            Hard | Perfect => 5,
+        };
+        let mut search = Search::new();
+        let (val, choices) = search.best_moves(&self.state, depth);
+        info!(
+            "{:?} searched {} positions, transposition table: {}",
+            self.props.difficulty,
+            search.nodes(),
+            search.table_stats()
+        );
 
-        let (val, (column, letter)) = val_choice;
+        let (column, letter) = if choices.is_empty() {
+            (-1, 'T')
+        } else {
+            let choice = self.choose(&choices);
+            (choice.column as i64, choice.letter.unwrap().as_char())
+        };
 
         self.paused = false;
         let mut done = self.action(column as usize, letter, true);
@@ -284,32 +150,6 @@ impl TootCanvasModel {
         }
     }
 
-    // TODO - add choice of letter
-    #[inline]
-    pub fn fill_map(&self, new_state: &Vec<Vec<i64>>, column: usize, value: i64) -> Vec<Vec<i64>> {
-        let mut temp_map = new_state.clone();
-        if temp_map[0][column] != 0 || column > 6 {
-            temp_map[0][0] = 999; // error code
-        }
-
-        let mut done = false;
-        let mut row = 0;
-
-        for i in 0..5 {
-            if temp_map[i + 1][column] != 0 {
-                done = true;
-                row = i;
-                break;
-            }
-        }
-        if !done {
-            row = 5;
-        }
-
-        temp_map[row][column] = value;
-        return temp_map;
-    }
-
     #[inline]
     pub fn draw_circle(&self, x: u32, y: u32, fill: &str, stroke: &str, text: &str) {
         let context = self.ctx.as_ref().unwrap();
@@ -355,20 +195,15 @@ impl TootCanvasModel {
     pub fn draw(&self) {
         for y in 0..6 {
             for x in 0..7 {
-                let mut text = "";
+                let mut text = String::new();
                 let mut fg_color = "transparent";
-                if self.map[y][x] >= 1 && self.dummy_map[y][x] == 'T' {
-                    fg_color = "#99ffcc";
-                    text = "T";
-                } else if self.map[y][x] >= 1 && self.dummy_map[y][x] == 'O' {
-                    fg_color = "#99ffcc";
-                    text = "O";
-                } else if self.map[y][x] <= -1 && self.dummy_map[y][x] == 'T' {
-                    fg_color = "#ffff99";
-                    text = "T";
-                } else if self.map[y][x] <= -1 && self.dummy_map[y][x] == 'O' {
-                    fg_color = "#ffff99";
-                    text = "O";
+                if let Some(cell) = self.state.board().get(y, x) {
+                    fg_color = if cell.player.value() >= 1 {
+                        "#99ffcc"
+                    } else {
+                        "#ffff99"
+                    };
+                    text = cell.letter.map(|letter| letter.as_char().to_string()).unwrap_or_default();
                 }
 
                 self.draw_circle(
@@ -376,7 +211,7 @@ impl TootCanvasModel {
                     (75 * y + 50) as u32,
                     &fg_color,
                     "black",
-                    text,
+                    &text,
                 );
             }
         }
@@ -384,55 +219,10 @@ impl TootCanvasModel {
 
     #[inline]
     pub fn check(&mut self) {
-        let (mut temp_r, mut temp_b, mut temp_br, mut temp_tr) =
-            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
-        for i in 0..6 {
-            for j in 0..7 {
-                temp_r = vec!['a'; 4];
-                temp_b = vec!['a'; 4];
-                temp_br = vec!['a'; 4];
-                temp_tr = vec!['a'; 4];
-                for k in 0..=3 {
-                    if j + k < 7 {
-                        temp_r[k] = self.dummy_map[i][j + k];
-                    }
-
-                    if i + k < 6 {
-                        temp_b[k] = self.dummy_map[i + k][j];
-                    }
-
-                    if i + k < 6 && j + k < 7 {
-                        temp_br[k] = self.dummy_map[i + k][j + k];
-                    }
-
-                    if i >= k && j + k < 7 {
-                        temp_tr[k] = self.dummy_map[i - k][j + k];
-                    }
-                }
-                let toot = "TOOT";
-                let otto = "OTTO";
-                if String::from_iter(temp_r.clone()) == toot {
-                    self.win(1);
-                } else if String::from_iter(temp_r) == otto {
-                    self.win(-1);
-                } else if String::from_iter(temp_b.clone()) == toot {
-                    self.win(1);
-                } else if String::from_iter(temp_b) == otto {
-                    self.win(-1);
-                } else if String::from_iter(temp_br.clone()) == toot {
-                    self.win(1);
-                } else if String::from_iter(temp_br) == otto {
-                    self.win(-1);
-                } else if String::from_iter(temp_tr.clone()) == toot {
-                    self.win(1);
-                } else if String::from_iter(temp_tr) == otto {
-                    self.win(-1);
-                }
-            }
-        }
-        // check if draw
-        if self.current_move == 42 && !self.won {
-            self.win(0);
+        match self.state.outcome() {
+            Some(Outcome::Win(player)) => self.win(player.value()),
+            Some(Outcome::Draw) => self.win(0),
+            None => {}
         }
     }
 
@@ -453,11 +243,7 @@ impl TootCanvasModel {
 
     #[inline]
     pub fn player_move(&self) -> i64 {
-        match self.letter.as_str() {
-            "T" => 1,
-            "O" => -1,
-            _ => 0, // unreachable
-        }
+        self.state.to_move().value()
     }
 
     pub fn animate(
@@ -493,42 +279,181 @@ impl TootCanvasModel {
                 cloned.emit((column, current_move, letter, to_row, cur_pos+25, mode));
             }));
         } else {
-            self.map[to_row][column] = if letter == 'T' { 1 } else { -1 };
-            self.dummy_map[to_row][column] = letter;
-            self.current_move += 1;
+            let letter = Letter::from_char(letter).unwrap();
+            self.state.apply_move(Move::letter(column, letter)).unwrap();
+            self.animating = false;
             self.draw();
             self.check();
-            if mode == false && self.props.player2.as_ref().unwrap() == "Computer" {
+            if self.props.replay.is_some() {
+                // the step may have moved on while the disc was falling
+                self.reject_click = false;
+                self.sync_replay();
+            } else if mode == false && !self.won && self.props.player2.as_ref().unwrap() == "Computer" {
                 self.ai(-1);
             } else {
                 self.reject_click = false;
+                self.save_progress();
             }
         }
     }
 
-    #[inline(always)]
-    pub fn action(&mut self, column: usize, letter: char, mode: bool) -> i64 {
-        if self.paused || self.won {
-            return 0;
+    // keeps the unfinished game for the page to offer resuming, against the computer
+    // only once it has replied
+    fn save_progress(&self) {
+        if self.props.replay.is_some() || self.won {
+            return;
+        }
+        if self.vs_computer() && self.state.to_move() != Player::One {
+            return;
         }
+        if self.state.move_count() == 0 {
+            saved_game::clear(&self.canvas_id);
+            return;
+        }
+        let saved = SavedGame {
+            player1: self.props.player1.clone().unwrap_or_default(),
+            player2: self.props.player2.clone().unwrap_or_default(),
+            difficulty: self.props.difficulty,
+            variant: Variant::STANDARD,
+            pop_out: false,
+            power_up: false,
+            five_in_a_row: false,
+            moves: self.state.history().to_vec(),
+            used_undo: self.used_undo,
+        };
+        saved_game::save(&self.canvas_id, &saved);
+    }
+
+    // puts the board back to a saved game
+    fn resume(&mut self, saved: &SavedGame) {
+        self.reset();
+        for &mv in &saved.moves {
+            if self.state.apply_move(mv).is_err() {
+                break;
+            }
+        }
+        self.used_undo = saved.used_undo;
+        self.redraw();
+        self.check();
+    }
+
+    // against the computer a turn is the player's move and the reply to it
+    fn vs_computer(&self) -> bool {
+        self.props.player2.as_ref().map_or(false, |name| name == "Computer")
+    }
+
+    fn can_undo(&self) -> bool {
+        !self.animating && !self.won && self.state.move_count() > 0
+    }
+
+    fn can_redo(&self) -> bool {
+        !self.animating && !self.won && !self.undone.is_empty()
+    }
+
+    // takes back the last turn, leaving the board with the same player to move
+    pub fn undo(&mut self) {
+        if !self.can_undo() {
+            return;
+        }
+        while let Some(mv) = self.state.undo_move() {
+            self.undone.push(mv);
+            if !self.vs_computer() || self.state.to_move() == Player::One {
+                break;
+            }
+        }
+        self.used_undo = true;
+        self.redraw();
+        self.save_progress();
+    }
+
+    // plays back the last turn taken back by undo
+    pub fn redo(&mut self) {
+        if !self.can_redo() {
+            return;
+        }
+        while let Some(mv) = self.undone.pop() {
+            if self.state.apply_move(mv).is_err() {
+                self.undone.clear();
+                break;
+            }
+            if !self.vs_computer() || self.state.to_move() == Player::One {
+                break;
+            }
+        }
+        self.redraw();
+        self.check();
+        self.save_progress();
+    }
+
+    fn redraw(&mut self) {
+        self.paused = false;
+        self.reject_click = false;
+        self.clear();
+        self.draw();
+        self.draw_mask();
+    }
+
+    // an online game waiting for a click from this browser
+    fn local_turn(&self) -> bool {
+        let synced = match &self.props.replay {
+            Some(moves) => moves.len() == self.state.move_count(),
+            None => false,
+        };
+        synced
+            && !self.animating
+            && !self.won
+            && self.props.local_player == Some(self.state.to_move())
+    }
 
-        if self.map[0][column] != 0 || column > 6 {
-            return -1;
+    // brings the board to the first replay_step moves of the replay, a single step
+    // forward is animated and anything else is redrawn straight away
+    fn sync_replay(&mut self) {
+        let moves = match self.props.replay.clone() {
+            Some(moves) => moves,
+            None => return,
+        };
+        if self.animating {
+            return;
+        }
+
+        let step = std::cmp::min(self.props.replay_step, moves.len());
+        while self.state.move_count() > step || !moves.starts_with(self.state.history()) {
+            self.state.undo_move();
         }
+        self.paused = false;
+        self.won = false;
 
-        let mut done = false;
-        let mut row = 0;
-        for i in 0..5 {
-            if self.map[i + 1][column] != 0 {
-                done = true;
-                row = i;
+        if self.state.move_count() + 1 == step {
+            let mv = moves[step - 1];
+            if let Some(letter) = mv.letter {
+                self.action(mv.column, letter.as_char(), true);
+                return;
+            }
+        }
+        while self.state.move_count() < step {
+            let mv = moves[self.state.move_count()];
+            if self.state.apply_move(mv).is_err() {
                 break;
             }
         }
-        if !done {
-            row = 5;
+        self.redraw();
+        self.check();
+    }
+
+    #[inline(always)]
+    pub fn action(&mut self, column: usize, letter: char, mode: bool) -> i64 {
+        if self.paused || self.won {
+            return 0;
         }
 
+        // rejects full columns and letters the player has run out of
+        let mv = Move::letter(column, Letter::from_char(letter).unwrap());
+        let row = match self.state.validate(mv) {
+            Ok(row) => row,
+            Err(_) => return -1,
+        };
+
+        self.animating = true;
         self.animate(column, self.player_move(), letter, row, 0, mode);
 
         self.paused = true;
@@ -550,7 +475,11 @@ impl TootCanvasModel {
             msg = "It's a draw".to_string();
         }
 
-        let to_print = format!("{} - Click on game board to reset", msg);
+        let to_print = if self.props.replay.is_some() {
+            msg
+        } else {
+            format!("{} - Click on game board to reset", msg)
+        };
 
         let context = self.ctx.as_ref().unwrap();
         context.save();
@@ -558,6 +487,14 @@ impl TootCanvasModel {
         context.set_fill_style_color("#111");
         context.fill_text(&to_print, 150.0, 20.0, None);
 
+        // replays show a finished game, it has been saved already
+        if self.props.replay.is_some() {
+            context.restore();
+            return;
+        }
+
+        saved_game::clear(&self.canvas_id);
+
         // construct game to post
         let game = Game {
             gameNumber: String::new(),
@@ -572,26 +509,53 @@ impl TootCanvasModel {
                 String::from("Draw")
             },
             GameDate: Date::now() as u64,
+            Player1Inventory: self.state.inventory(Player::One),
+            Player2Inventory: self.state.inventory(Player::Two),
+            Moves: self.state.history().to_vec(),
+            UsedUndo: self.used_undo,
+            Difficulty: if self.props.player2.as_ref().unwrap() == "Computer" {
+                Some(self.props.difficulty.to_string())
+            } else {
+                None
+            },
+            ClientId: Some(offline::new_client_id()),
+            Variant: None,
         };
 
-        // construct callback
-        let callback = self
-            .link
-            .callback(move |response: Response<Result<String, Error>>| {
-                info!("successfully saved!");
-                Message::Ignore
-            });
+        // queued first, so the result survives the server being down
+        offline::enqueue(&game);
+        self.save_status = String::from("Saving...");
+        self.fetch_task = offline::send(&mut self.fetch_service, &self.link, &game, Message::Saved);
+        if self.fetch_task.is_none() {
+            self.save_status = Saved::Queued.message();
+        }
 
-        // construct request
-        let request = Request::post("/games")
-            .header("Content-Type", "application/json")
-            .body(Json(&game))
-            .unwrap();
+        context.restore();
+    }
+}
 
-        // send the request
-        self.fetch_task = self.fetch_service.fetch(request, callback).ok();
+impl TootCanvasModel {
+    fn view_controls(&self) -> Html {
+        if self.props.replay.is_some() {
+            return html! {};
+        }
+        html! {
+            <div>
+                <button onclick=self.link.callback(|_| Message::Undo) disabled={!self.can_undo()}>{"Undo"}</button>
+                <button onclick=self.link.callback(|_| Message::Redo) disabled={!self.can_redo()}>{"Redo"}</button>
+            </div>
+        }
+    }
 
-        context.restore();
+    fn view_inventory(&self, player: Player, name: &Option<String>) -> Html {
+        let inventory = self.state.inventory(player).unwrap();
+        html! {
+            <p>
+                <b>{ name.as_ref().map_or("", String::as_str) }</b>
+                <br></br>
+                { format!("T: {}   O: {}", inventory.t, inventory.o) }
+            </p>
+        }
     }
 }
 
@@ -603,9 +567,6 @@ impl Component for TootCanvasModel {
         let canvas_id = props.canvas_id.clone().unwrap();
         let letter = props.letter.clone();
 
-        let mut map: Vec<Vec<i64>> = vec![vec![0; 7]; 6];
-        let mut dummy_map: Vec<Vec<char>> = vec![vec!['a'; 7]; 6];
-
         Self {
             props,
             canvas_id,
@@ -614,15 +575,17 @@ impl Component for TootCanvasModel {
             cbk: link.callback(|e: ClickEvent| Message::Click(e)),
             animate_cbk: link
                 .callback(|e: (usize, i64, char, usize, usize, bool)| Message::AnimateCallback(e)),
-            map,
-            dummy_map,
-            current_move: 0,
+            state: GameState::new(GameKind::TootOtto),
+            animating: false,
+            undone: Vec::new(),
+            used_undo: false,
             paused: false,
             won: false,
             reject_click: false,
             letter,
             fetch_service: FetchService::new(),
             fetch_task: None,
+            save_status: String::new(),
             link,
         }
     }
@@ -630,7 +593,7 @@ impl Component for TootCanvasModel {
     fn update(&mut self, message: Self::Message) -> ShouldRender {
         match message {
             Message::Click(e) => {
-                if self.reject_click {
+                if self.reject_click || (self.props.replay.is_some() && !self.local_turn()) {
                     return false;
                 }
 
@@ -648,9 +611,15 @@ impl Component for TootCanvasModel {
                         self.paused = false;
 
                         // TODO
-                        let valid = self.action(j, self.letter.chars().next().unwrap(), false);
+                        let letter = self.letter.chars().next().unwrap();
+                        let valid = self.action(j, letter, false);
                         if valid == 1 {
+                            if let Some(move_cbk) = &self.props.move_cbk {
+                                move_cbk.emit(Move::letter(j, Letter::from_char(letter).unwrap()));
+                            }
                             self.reject_click = true;
+                            // a new move replaces whatever was taken back
+                            self.undone.clear();
                         };
 
                         break;
@@ -660,6 +629,9 @@ impl Component for TootCanvasModel {
             Message::AnimateCallback((a, b, c, d, e, f)) => {
                 self.animate(a, b, c, d, e, f);
             }
+            Message::Undo => self.undo(),
+            Message::Redo => self.redo(),
+            Message::Saved(saved) => self.save_status = saved.message(),
             Message::Ignore => (),
         };
 
@@ -668,7 +640,16 @@ impl Component for TootCanvasModel {
 
     fn view(&self) -> Html {
         html! {
-            <canvas id={&self.canvas_id} height="480" width="640"></canvas>
+            <div style="display: flex">
+                <canvas id={&self.canvas_id} height="480" width="640"></canvas>
+                <div>
+                    <h5><b>{"Letters Left"}</b></h5>
+                    { self.view_inventory(Player::One, &self.props.player1) }
+                    { self.view_inventory(Player::Two, &self.props.player2) }
+                    { self.view_controls() }
+                    <p>{ &self.save_status }</p>
+                </div>
+            </div>
         }
     }
 
@@ -687,13 +668,25 @@ impl Component for TootCanvasModel {
 
         // clears and draws mask
         self.reset();
+        if let Some(saved) = self.props.resume.clone() {
+            self.resume(&saved);
+        }
+        self.sync_replay();
 
         true
     }
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let resume = props.resume.is_some() && props.resume != self.props.resume;
         self.props = props;
         self.letter = self.props.letter.clone();
+        if self.ctx.is_some() {
+            if resume {
+                let saved = self.props.resume.clone().unwrap();
+                self.resume(&saved);
+            }
+            self.sync_replay();
+        }
         true
     }
 }